@@ -5,9 +5,16 @@ use crate::{WIDTH, HEIGHT, BORDERS, Error};
 use rand::prelude::*;
 use std::iter::Flatten;
 use std::slice::Iter;
+use std::collections::VecDeque;
+use std::cmp;
 
 type CellMap = Map2d<Cell>;
 
+// how far radiant heat reaches
+const RADIATION_RANGE: i32 = 12;
+// frames concrete takes to set at room temperature, unless changed
+const DEFAULT_CURE_TIME: u32 = 900;
+
 pub struct SandApi {
     x: i32,
     y: i32,
@@ -17,7 +24,41 @@ pub struct SandApi {
     paused: bool,
     map: CellMap,
     pub highlighted: sdl2::rect::Point,
-    cloned_cells: Zone<Species>,
+    cloned_cells: Zone<Template>,
+    clone_emission: Emission,
+    spouts: Zone<Spout>,
+    support: Map2d<bool>,
+    gravity: Gravity,
+    cure_time: u32,
+}
+
+// which way is down, and how hard things are pulled that way. the larger
+// axis picks the direction, and the length is the chance per frame that
+// gravity gets a go at a loose cell, so 1 is normal and 0 is weightless.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gravity {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Gravity {
+    pub const DOWN: Gravity = Gravity { x: 0.0, y: 1.0 };
+    pub const ZERO: Gravity = Gravity { x: 0.0, y: 0.0 };
+
+    pub fn strength(&self) -> f32 {
+        f32::sqrt(self.x * self.x + self.y * self.y)
+    }
+
+    // down, as a unit step
+    fn down(&self) -> (i32, i32) {
+        if self.strength() == 0.0 {
+            (0, 1)
+        } else if self.x.abs() > self.y.abs() {
+            (self.x.signum() as i32, 0)
+        } else {
+            (0, self.y.signum() as i32)
+        }
+    }
 }
 
 pub struct Neighbor {
@@ -39,6 +80,11 @@ impl SandApi {
             borders: BORDERS,
             highlighted: sdl2::rect::Point::new(0, 0),
             cloned_cells: Zone::new(),
+            clone_emission: Emission::DEFAULT,
+            spouts: Zone::new(),
+            support: Map2d::filled_with(false, WIDTH as i32, HEIGHT as i32),
+            gravity: Gravity::DOWN,
+            cure_time: DEFAULT_CURE_TIME,
         };
         if api.borders {
             //walls
@@ -60,12 +106,31 @@ impl SandApi {
         *self = Self::new();
     }
 
-    pub fn store_cloned_cell(&mut self, species: Species) -> Option<CloneId> {
-        self.cloned_cells.insert(species)
+    // clones copy the whole cell they first touch, and put copies out the
+    // way new clones are currently set to
+    pub fn store_cloned_cell(&mut self, cell: Cell) -> Option<CloneId> {
+        self.cloned_cells.insert(Template { cell, emission: self.clone_emission })
+    }
+
+    pub fn get_cloned_cell(&mut self, id: CloneId) -> Template {
+        self.cloned_cells.get(id).unwrap()
+    }
+
+    pub fn set_clone_emission(&mut self, emission: Emission) {
+        self.clone_emission = emission;
     }
 
-    pub fn get_cloned_cell(&mut self, id: CloneId) -> Cell {
-        Cell::new(self.cloned_cells.get(id).unwrap())
+    pub fn store_spout(&mut self, spout: Spout) -> Option<SpoutId> {
+        self.spouts.insert(spout)
+    }
+
+    // none if nothing on the map was using it, and it's been freed
+    pub fn get_spout(&self, id: SpoutId) -> Option<Spout> {
+        self.spouts.contents.get(id as usize).copied().flatten()
+    }
+
+    pub fn remove_spout(&mut self, id: SpoutId) {
+        self.spouts.remove(id);
     }
 
     pub fn update(&mut self) -> Result<(), Error> {
@@ -73,27 +138,37 @@ impl SandApi {
             return Ok(()) 
         }
 
-        for y in 0..HEIGHT {
-            let y = HEIGHT - y;
+        self.update_support()?;
+
+        // sweep from whichever side is down, so a falling pile moves as one
+        let (down_x, down_y) = self.gravity.down();
+        let (rows, across) = if down_x == 0 { (HEIGHT, WIDTH) } else { (WIDTH, HEIGHT) };
+        for row in 0..rows {
+            let row = rows - row;
+            let depth = if down_x + down_y > 0 { row } else { rows - row };
             // bias from left to right every even row...
-            for x in (0..WIDTH) {
-                let mut x = x;
-                if y % 2 == 0 {
-                    x = WIDTH - x
+            for i in 0..across {
+                let mut i = i;
+                if row % 2 == 0 {
+                    i = across - i
                 }
+                let (x, y) = if down_x == 0 { (i, depth) } else { (depth, i) };
                 self.set_cursor(x as i32, y as i32);
                 self.update_heat()?;
                 self.update_cell()?;
             }
         }
 
-        // keep track of cloned cells
+        // keep track of cloned cells and spouts
         let mut clone_ids = Vec::new();
+        let mut spout_ids = Vec::new();
         for x in 0..WIDTH{
             for y in 0..HEIGHT {
                 let cell = self.get_absolute(x as i32, y as i32)?;
-                if let Species::Clone(Some(id)) = cell.species {
-                    clone_ids.push(id);
+                match cell.species {
+                    Species::Clone(Some(id)) => clone_ids.push(id),
+                    Species::Spout(Some(id)) => spout_ids.push(id),
+                    _ => {}
                 }
             }
         }
@@ -102,6 +177,11 @@ impl SandApi {
                 self.cloned_cells.remove(i.into());
             }
         }
+        for i in 0..self.spouts.len() as u16 {
+            if !spout_ids.contains(&i) {
+                self.spouts.remove(i);
+            }
+        }
 
         for x in 0..WIDTH{
             for y in 0..HEIGHT{
@@ -115,32 +195,119 @@ impl SandApi {
         self.y = 0;
         if !self.borders {
             for y in 0..self.height {
-                self.set(0, y, EMPTY)?;
-                self.set(self.width as i32 - 1, y, EMPTY)?;
+                self.set_absolute(0, y, EMPTY)?;
+                self.set_absolute(self.width as i32 - 1, y, EMPTY)?;
             }
             for x in 0..self.width {
-                self.set(x, 0, EMPTY)?;
-                self.set(x, self.height as i32 - 1, EMPTY)?;
+                self.set_absolute(x, 0, EMPTY)?;
+                self.set_absolute(x, self.height as i32 - 1, EMPTY)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // works out which solids are held up. a solid resting on something that
+    // isn't fluid, or on a wall, is supported; so is anything stacked on top of
+    // a supported solid, or hanging off one to the side or below, as long as
+    // it's no further out than its strength allows.
+    fn update_support(&mut self) -> Result<(), Error> {
+        let mut reach = Map2d::filled_with(u8::MAX, self.width, self.height);
+        let mut queue = VecDeque::new();
+        let (down_x, down_y) = self.orient(0, 1);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = self.get_absolute(x, y)?;
+                let below = self.get_absolute(x + down_x, y + down_y)?;
+                if matches!(cell.species, Species::Wall | Species::Border)
+                || (cell.is_structural() && !below.is_fluid() && !below.is_structural()) {
+                    reach.set_point(x, y, 0)?;
+                    queue.push_back((x, y));
+                }
             }
         }
 
+        while let Some((x, y)) = queue.pop_front() {
+            let d = reach.retrieve(x, y)?;
+            for &(dx, dy, cost) in [(0, -1, 0), (-1, 0, 1), (1, 0, 1), (0, 1, 1)].iter() {
+                let (dx, dy) = self.orient(dx, dy);
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= self.width || ny >= self.height {
+                    continue
+                }
+                let neighbor = self.get_absolute(nx, ny)?;
+                let nd = d.saturating_add(cost);
+                if !neighbor.is_structural() 
+                || nd > neighbor.species.strength() 
+                || nd >= reach.retrieve(nx, ny)? {
+                    continue
+                }
+                reach.set_point(nx, ny, nd)?;
+                if cost == 0 {
+                    queue.push_front((nx, ny));
+                } else {
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.support.set_point(x, y, reach.retrieve(x, y)? != u8::MAX)?;
+            }
+        }
         Ok(())
     }
 
+    pub fn is_supported(&self, dx: i32, dy: i32) -> bool {
+        let (dx, dy) = self.orient(dx, dy);
+        self.support.retrieve(self.x + dx, self.y + dy).unwrap_or(false)
+    }
+
+    pub fn gravity(&self) -> Gravity {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Gravity) {
+        self.gravity = gravity;
+    }
+
+    pub fn cure_time(&self) -> u32 {
+        self.cure_time
+    }
+
+    pub fn set_cure_time(&mut self, frames: u32) {
+        self.cure_time = frames;
+    }
+
+    pub fn is_weightless(&self) -> bool {
+        self.gravity.strength() == 0.0
+    }
+
+    // rolls for whether gravity acts on a loose cell this frame
+    pub fn gravity_pulls(&self) -> bool {
+        let strength = self.gravity.strength().min(1.0) as f64;
+        strength > 0.0 && thread_rng().gen_bool(strength)
+    }
+
+    // relative positions given to `get`, `set` and friends are in gravity's
+    // frame, where +y is down. this turns one into an actual offset on the map.
+    pub fn orient(&self, dx: i32, dy: i32) -> (i32, i32) {
+        match self.gravity.down() {
+            (0, -1) => (-dx, -dy),
+            (1, 0) => (dy, -dx),
+            (-1, 0) => (-dy, dx),
+            _ => (dx, dy),
+        }
+    }
+
     fn update_heat(&mut self) -> Result<(), Error> {
         use Species::*;
         let mut cell = self.get(0, 0)?;
-        if matches!(cell.species, Empty | Clone(_) | Border ) {
+        if matches!(cell.species, Empty | Clone(_) | Border | Spout(_) | Drain | Void ) {
             return Ok(())
         }
         let mut rng = thread_rng();
-        if cell.is_flammable() {
-            if cell.heat >= 3000 {
-                cell.species = BlueFire
-            } else if cell.heat >= 800 {
-                cell.species = Fire
-            }
-        }
         let mut alone = true;
         let neighbors = self.neighbors()?;
         for n in neighbors.iter() {
@@ -152,8 +319,10 @@ impl SandApi {
             }
             let mut changed = false;
             if cell.heat > neighbor.heat + 10 {
-                cell.heat -= 10;
-                neighbor.heat += 10;
+                let rate = cmp::min(cell.species.conductivity(), neighbor.species.conductivity());
+                let flow = cmp::max(10, cmp::min(rate, (cell.heat - neighbor.heat) / 2));
+                cell.heat -= flow;
+                neighbor.heat += flow;
                 changed = true;
             } else if cell.heat == neighbor.heat {
                 if rng.gen_bool(0.4) {
@@ -172,9 +341,61 @@ impl SandApi {
         if cell.heat < cell.species.starting_temp() {
             cell.heat += 1;
         }
+        // current warms a conductor as it passes through, more so when
+        // it's being fed from several sides at once
+        if let Some(conductor) = cell.species.conductor().filter(|_| cell.charge == Cell::SPARK) {
+            let feeding = neighbors.iter()
+                .filter(|n| n.cell.charge == Cell::SPARK || n.cell.species == Battery)
+                .count() as i16;
+            cell.heat = cell.heat.saturating_add(conductor.resistance * feeding.max(1));
+        }
+        // decay keeps radioactive material warm
+        if let Some(radioactive) = cell.species.radioactive() {
+            cell.heat = cell.heat.saturating_add(radioactive.warmth);
+        }
+        if cell.radiates() {
+            self.radiate_heat(&mut cell)?;
+        }
+
+        // scorched
+        if cell.heat > 600 && rng.gen_bool(0.1) && cell.wear(cell.species.toughness().heat) {
+            return self.set(0, 0, cell.worn_out())
+        }
         self.set(0,0,cell)
     }
 
+    // sends heat out along a random line of sight, to the first thing in the
+    // way that isn't air or gas. rays spread out, so the odds of a cell being
+    // hit drop off with distance, and on top of that what it does get also
+    // drops off with distance.
+    fn radiate_heat(&mut self, cell: &mut Cell) -> Result<(), Error> {
+        let mut rng = thread_rng();
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let tx = (angle.cos() * RADIATION_RANGE as f32).round() as i32;
+        let ty = (angle.sin() * RADIATION_RANGE as f32).round() as i32;
+        let mut path = util::line(0, 0, tx, ty);
+        path.sort_by_key(|p| p.x.abs() + p.y.abs());
+
+        for p in path.iter().filter(|p| (p.x, p.y) != (0, 0)) {
+            let mut target = self.get(p.x, p.y)?;
+            if target.species == Species::Empty || target.is_gas() {
+                continue
+            }
+            if target.species == Species::Border {
+                break
+            }
+            let d = util::distance(0, 0, p.x, p.y);
+            let gain = ((cell.heat / 100) as f32 / d) as i16;
+            if gain > 0 && target.heat < cell.heat {
+                target.heat = cmp::min(target.heat + gain, cell.heat);
+                cell.heat -= gain;
+                self.set(p.x, p.y, target)?;
+            }
+            break
+        }
+        Ok(())
+    }
+
     pub fn toggle_pause(&mut self) {
         self.paused = !self.paused;
     }
@@ -189,17 +410,14 @@ impl SandApi {
             cell.clock = true; 
         }
 
-        if cell.turns_to_lava() && cell.heat > 1600 {
-            let mut rng = thread_rng();
-            if rng.gen::<usize>() % 100 < 5 {
-                cell.species = Lava;
-                self.set(0, 0, cell)?;
-            }
-        } 
+        if detonate(self, &mut cell)? || change_phase(self, &mut cell)? || burn(self, &mut cell)? {
+            return Ok(())
+        }
+        conduct(self, &mut cell)?;
 
         match cell.species {
             Sand => update_sand(self, cell)?,
-            Water => update_water(self, cell)?, 
+            Water | MuddyWater(_) => update_water(self, cell)?, 
             Mud(_) => update_mud(self, cell)?,
             Acid => update_acid(self, cell)?,
             Grass => update_grass(self, cell)?,
@@ -210,11 +428,32 @@ impl SandApi {
             Lava => update_lava(self, cell)?,
             Steam => update_steam(self, cell)?,
             Salt => update_salt(self, cell)?,
-            SaltWater => update_salt_water(self, cell)?,
+            SaltWater(_) => update_salt_water(self, cell)?,
             Fire => update_fire(self, cell)?,
             BlueFire => update_fire(self, cell)?,
-            Ice => update_ice(self, cell)?,
             Clone(_) => update_clone(self, cell)?,
+            Smoke => update_smoke(self, cell)?,
+            Ash => update_powder(self, cell)?,
+            Charcoal => update_coarse(self, cell)?,
+            Gunpowder => update_powder(self, cell)?,
+            Nitro | Oil | Gasoline | MoltenGlass | MoltenIron | MoltenCopper | MoltenSalt => update_liquid(self, cell)?,
+            Hydrogen | CarbonDioxide | Methane => update_gas(self, cell)?,
+            Stone | Ice | Wire | Battery | Wood | Glass | Basalt | Obsidian | Brick => update_solid(self, cell)?,
+            Gravel => update_powder(self, cell)?,
+            Clay => update_coarse(self, cell)?,
+            BlackHole | Repulsor => update_attractor(self, cell)?,
+            Seed => update_seed(self, cell)?,
+            Trunk(_) | Branch(_) => update_tree(self, cell)?,
+            Leaf => update_leaf(self, cell)?,
+            GrassSeed => update_grass_seed(self, cell)?,
+            Withered => update_withered(self, cell)?,
+            Concrete(_, _) => update_concrete(self, cell)?,
+            Iron | Copper => update_metal(self, cell)?,
+            Rust | Lead => update_powder(self, cell)?,
+            Uranium | Plutonium => update_radioactive(self, cell)?,
+            Spout(_) => update_spout(self, cell)?,
+            Drain => update_drain(self, cell)?,
+            Void => update_void(self, cell)?,
             _             => {}
         };
 
@@ -223,6 +462,7 @@ impl SandApi {
     }
 
     pub fn get(&self, dx: i32, dy: i32) -> Result<Cell, Error> {
+        let (dx, dy) = self.orient(dx, dy);
         let nx = self.x + dx;
         let ny = self.y + dy;
         let cell = self.map.retrieve(nx, ny)?;
@@ -230,6 +470,7 @@ impl SandApi {
     }
 
     pub fn set(&mut self, dx: i32, dy: i32, cell: Cell) -> Result<(), Error> {
+        let (dx, dy) = self.orient(dx, dy);
         let nx = self.x + dx;
         let ny = self.y + dy;
         self.map.set_point(nx, ny, cell)?;
@@ -246,14 +487,17 @@ impl SandApi {
         Ok(cell)
     }
 
-    pub fn brush(&mut self, x: i32, y: i32, radius: i32, mut cell: Cell) -> Result<(), Error> {
+    // returns whether anything was placed
+    pub fn brush(&mut self, x: i32, y: i32, radius: i32, mut cell: Cell) -> Result<bool, Error> {
+        let mut placed = false;
         if radius == 1 {
             if let Ok(c) = self.get_absolute(x, y) {
                 if c == EMPTY {
                     self.set_absolute(x, y, cell)?;
+                    placed = true;
                 }
             }
-            return Ok(());
+            return Ok(placed);
 
         }
         for a in -radius..=radius {
@@ -264,11 +508,12 @@ impl SandApi {
                     if util::distance(x, y, nx, ny) <= radius as f32 && c == EMPTY {
                         cell.regrain();
                         self.set_absolute(nx, ny, cell)?;
+                        placed = true;
                     }
                 }
             }
         }
-        Ok(())
+        Ok(placed)
     }
 
     pub fn erase (&mut self, x: i32, y: i32, radius: i32) -> Result<(), Error> {
@@ -2,16 +2,22 @@ use rand::prelude::*;
 use rand::seq::SliceRandom;
 use crate::api::*;
 use crate::Error;
-use crate::util::line;
+use crate::util::{line, distance};
 use sdl2::pixels::Color;
 use std::cmp;
+use std::collections::VecDeque;
 use sdl2::hint::get_video_minimize_on_focus_loss;
 use crate::cell::Species::Empty;
 use std::hint::unreachable_unchecked;
 
 type Wetness = u8;
 type Height = u8;
+type Cure = u8;
+type Fuel = u8;
+type Salinity = u8;
+type Sediment = u8;
 pub type CloneId = u16;
+pub type SpoutId = u16;
 
 #[derive(Clone, Debug, Copy, PartialEq, Hash)]
 pub enum Species {
@@ -28,7 +34,7 @@ pub enum Species {
     WaterGrass(Height),
     Flower(Color),
     Salt,
-    SaltWater,
+    SaltWater(Salinity),
     Steam,
     Lava,
     Stone,
@@ -36,6 +42,99 @@ pub enum Species {
     BlueFire,
     Ice,
     Clone(Option<CloneId>),
+    Smoke,
+    Ash,
+    Charcoal,
+    Gunpowder,
+    Nitro,
+    Hydrogen,
+    CarbonDioxide,
+    Gravel,
+    Clay,
+    MuddyWater(Sediment),
+    BlackHole,
+    Repulsor,
+    Wire,
+    Battery,
+    Oil,
+    Gasoline,
+    Methane,
+    Wood,
+    Seed,
+    // growing tips of a tree, with how much further they have to go
+    Trunk(Height),
+    Branch(Height),
+    Leaf,
+    GrassSeed,
+    // dead plant matter, on its way back to soil
+    Withered,
+    // how far along it is setting, and how much extra water it's taken on
+    Concrete(Cure, Wetness),
+    MoltenGlass,
+    Glass,
+    // lava that cooled quickly, or was quenched outright
+    Basalt,
+    Obsidian,
+    Iron,
+    Copper,
+    MoltenIron,
+    MoltenCopper,
+    Rust,
+    Uranium,
+    Plutonium,
+    Lead,
+    Spout(Option<SpoutId>),
+    Drain,
+    Void,
+    MoltenSalt,
+    // fired clay
+    Brick,
+}
+
+// what a clone makes copies of, and how it puts them out
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Template {
+    pub cell: Cell,
+    pub emission: Emission,
+}
+
+// the chance per frame a clone puts a copy into each free neighbor, and which
+// ways on the map it's allowed to. diagonals need both of their sides.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Emission {
+    pub rate: f64,
+    pub directions: u8,
+}
+
+impl Emission {
+    pub const UP: u8 = 1;
+    pub const DOWN: u8 = 2;
+    pub const LEFT: u8 = 4;
+    pub const RIGHT: u8 = 8;
+    pub const ALL: u8 = 15;
+    pub const DEFAULT: Emission = Emission { rate: 0.05, directions: Self::ALL };
+
+    pub fn allows(&self, dx: i32, dy: i32) -> bool {
+        let open = |direction: u8| self.directions & direction != 0;
+        (dy >= 0 || open(Self::UP))
+        && (dy <= 0 || open(Self::DOWN))
+        && (dx >= 0 || open(Self::LEFT))
+        && (dx <= 0 || open(Self::RIGHT))
+    }
+
+    pub fn toggle(&mut self, direction: u8) {
+        self.directions ^= direction;
+    }
+}
+
+// what a spout puts out, the chance per frame it does, and which way on the
+// map it points
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Spout {
+    pub species: Species,
+    pub rate: f64,
+    pub dx: i32,
+    pub dy: i32,
 }
 
 impl Species {
@@ -75,12 +174,370 @@ impl Species {
         use Species::*;
         match self {
             Lava => 5000,
+            MoltenGlass => 1800,
+            MoltenSalt => 1200,
+            MoltenIron => 1600,
+            MoltenCopper => 1200,
             Fire => 800,
             BlueFire => 3000,
             Ice => -160,
             _    => 20,
         }
     }
+
+    // phase transition table: what each species turns into when it's heated
+    // past or cooled below a point. the point a phase goes back at sits a bit
+    // past the point it came from, so cells don't flicker at the boundary.
+    pub fn phases(&self) -> Phases {
+        use Species::*;
+        let (heated, cooled) = match self {
+            Ice       => (Some(PhaseChange::new(Water, 0, LATENT_FUSION)), None),
+            Water     => (
+                Some(PhaseChange::new(Steam, 100, LATENT_VAPORIZATION)),
+                Some(PhaseChange::new(Ice, -3, LATENT_FUSION)),
+            ),
+            // the saltier it is, the higher it boils and the lower it freezes
+            SaltWater(salinity) => (
+                Some(PhaseChange::new(Steam, 100 + *salinity as i16 / 2, LATENT_VAPORIZATION)),
+                Some(PhaseChange::new(Ice, -3 - 2 * *salinity as i16, LATENT_FUSION)),
+            ),
+            MuddyWater(_) => (
+                Some(PhaseChange::new(Steam, 100, LATENT_VAPORIZATION)),
+                Some(PhaseChange::new(Ice, -3, LATENT_FUSION)),
+            ),
+            Steam     => (None, Some(PhaseChange::new(Water, 95, LATENT_VAPORIZATION))),
+            Lava      => (None, Some(PhaseChange::new(Stone, 1100, LATENT_ROCK_FUSION))),
+            Stone     => (Some(PhaseChange::new(Lava, 1200, LATENT_ROCK_FUSION)), None),
+            Gravel    => (Some(PhaseChange::new(Lava, 1200, LATENT_ROCK_FUSION)), None),
+            Salt      => (Some(PhaseChange::new(MoltenSalt, 1150, LATENT_ROCK_FUSION)), None),
+            MoltenSalt => (None, Some(PhaseChange::new(Salt, 1100, LATENT_ROCK_FUSION))),
+            // the organic part burns out of soil, leaving its sand behind
+            Soil      => (Some(PhaseChange::new(Sand, 600, LATENT_FUSION)), None),
+            Clay      => (Some(PhaseChange::new(Brick, 1000, LATENT_FUSION)), None),
+            Brick     => (Some(PhaseChange::new(Lava, 1500, LATENT_ROCK_FUSION)), None),
+            Mud(_)    => (Some(PhaseChange::new(MoltenGlass, 1700, LATENT_ROCK_FUSION)), None),
+            Sand      => (Some(PhaseChange::new(MoltenGlass, 1700, LATENT_ROCK_FUSION)), None),
+            Glass     => (Some(PhaseChange::new(MoltenGlass, 1600, LATENT_ROCK_FUSION)), None),
+            MoltenGlass => (None, Some(PhaseChange::new(Glass, 1500, LATENT_ROCK_FUSION))),
+            Basalt | Obsidian => (Some(PhaseChange::new(Lava, 1200, LATENT_ROCK_FUSION)), None),
+            Iron      => (Some(PhaseChange::new(MoltenIron, 1540, LATENT_METAL_FUSION)), None),
+            MoltenIron => (None, Some(PhaseChange::new(Iron, 1480, LATENT_METAL_FUSION))),
+            Copper    => (Some(PhaseChange::new(MoltenCopper, 1085, LATENT_METAL_FUSION)), None),
+            MoltenCopper => (None, Some(PhaseChange::new(Copper, 1040, LATENT_METAL_FUSION))),
+            _         => (None, None),
+        };
+        Phases { heated, cooled }
+    }
+
+    // what burns, and how. cells catch once they're at or past `ignition`.
+    pub fn combustion(&self) -> Option<Combustion> {
+        use Species::*;
+        match self {
+            Grass | GrassTip => Some(Combustion { ignition: 400, fuel: 20, heat_release: 30, smoke: 0.1, residue: Some(Ash) }),
+            Flower(_)        => Some(Combustion { ignition: 400, fuel: 10, heat_release: 30, smoke: 0.1, residue: None }),
+            Leaf             => Some(Combustion { ignition: 380, fuel: 15, heat_release: 30, smoke: 0.15, residue: Some(Ash) }),
+            Seed | GrassSeed => Some(Combustion { ignition: 300, fuel: 5, heat_release: 20, smoke: 0.05, residue: None }),
+            Withered         => Some(Combustion { ignition: 250, fuel: 8, heat_release: 25, smoke: 0.2, residue: Some(Ash) }),
+            Wood | Trunk(_) | Branch(_)
+                             => Some(Combustion { ignition: 300, fuel: 120, heat_release: 15, smoke: 0.1, residue: Some(Charcoal) }),
+            Charcoal         => Some(Combustion { ignition: 350, fuel: 200, heat_release: 12, smoke: 0.02, residue: Some(Ash) }),
+            Hydrogen         => Some(Combustion { ignition: 500, fuel: 2, heat_release: 200, smoke: 0.0, residue: Some(Steam) }),
+            Oil              => Some(Combustion { ignition: 300, fuel: 80, heat_release: 25, smoke: 0.3, residue: None }),
+            Gasoline         => Some(Combustion { ignition: 150, fuel: 25, heat_release: 40, smoke: 0.1, residue: None }),
+            Methane          => Some(Combustion { ignition: 540, fuel: 3, heat_release: 150, smoke: 0.0, residue: Some(CarbonDioxide) }),
+            _ => None,
+        }
+    }
+
+    pub fn gas(&self) -> Option<Gas> {
+        use Species::*;
+        match self {
+            Hydrogen      => Some(Gas { density: 1.0, diffusion: 0.5, bubble_rise: 0.9 }),
+            Methane       => Some(Gas { density: 5.5, diffusion: 0.4, bubble_rise: 0.8 }),
+            Steam         => Some(Gas { density: 6.0, diffusion: 0.3, bubble_rise: 0.6 }),
+            Smoke         => Some(Gas { density: 8.0, diffusion: 0.2, bubble_rise: 0.4 }),
+            CarbonDioxide => Some(Gas { density: 15.0, diffusion: 0.1, bubble_rise: 0.3 }),
+            _ => None,
+        }
+    }
+
+    pub fn liquid(&self) -> Option<Liquid> {
+        use Species::*;
+        match self {
+            Gasoline      => Some(Liquid { density: 0.75, viscosity: 0.0 }),
+            Oil           => Some(Liquid { density: 0.9, viscosity: 0.7 }),
+            Water | SaltWater(_) | MuddyWater(_) => Some(Liquid { density: 1.0, viscosity: 0.0 }),
+            Acid          => Some(Liquid { density: 1.2, viscosity: 0.0 }),
+            Nitro         => Some(Liquid { density: 1.6, viscosity: 0.0 }),
+            // thickens as it sets
+            Concrete(cure, _) => Some(Liquid { density: 2.4, viscosity: 0.6 + 0.4 * *cure as f64 / Cell::MAX_CURE as f64 }),
+            Lava          => Some(Liquid { density: 3.0, viscosity: 0.0 }),
+            MoltenGlass   => Some(Liquid { density: 2.5, viscosity: 0.8 }),
+            MoltenSalt    => Some(Liquid { density: 1.6, viscosity: 0.0 }),
+            MoltenIron    => Some(Liquid { density: 7.0, viscosity: 0.2 }),
+            MoltenCopper  => Some(Liquid { density: 8.0, viscosity: 0.2 }),
+            _ => None,
+        }
+    }
+
+    // what carries a spark. the saltier the water, the better it conducts.
+    pub fn conductor(&self) -> Option<Conductor> {
+        use Species::*;
+        let resistance = match self {
+            Copper => 1,
+            Wire => 2,
+            Iron => 3,
+            SaltWater(salinity) => (Cell::MAX_SALINITY + 1 - salinity) as i16 * 2,
+            _ => return None,
+        };
+        Some(Conductor { resistance })
+    }
+
+    pub fn attraction(&self) -> Option<Attraction> {
+        use Species::*;
+        match self {
+            BlackHole => Some(Attraction { strength: 3.0, range: 24, consumes: true }),
+            Repulsor  => Some(Attraction { strength: -3.0, range: 16, consumes: false }),
+            _ => None,
+        }
+    }
+
+    pub fn explosive(&self) -> Option<Explosive> {
+        use Species::*;
+        match self {
+            Gunpowder => Some(Explosive { detonation: 300, power: 6 }),
+            Nitro     => Some(Explosive { detonation: 150, power: 10 }),
+            Methane   => Some(Explosive { detonation: 600, power: 3 }),
+            _ => None,
+        }
+    }
+
+    pub fn toughness(&self) -> Toughness {
+        use Species::*;
+        let (acid, heat, impact) = match self {
+            Empty | Border | Wall | Clone(_) | BlackHole | Repulsor | Spout(_) | Drain | Void => (0, 0, 0),
+            Stone    => (8, 2, 25),
+            Basalt   => (6, 2, 20),
+            Brick    => (10, 1, 20),
+            Iron | Copper => (2, 0, 5),
+            // hard, but brittle
+            Glass | Obsidian => (0, 2, 50),
+            Gravel   => (15, 0, 40),
+            Ice      => (20, 0, 30),
+            Charcoal => (20, 5, 30),
+            Wood | Trunk(_) | Branch(_) => (40, 3, 20),
+            Wire     => (30, 1, 30),
+            Battery  => (30, 0, 30),
+            Grass | GrassTip | Flower(_) | WaterGrass(_) | Leaf | Seed | GrassSeed | Withered => (100, 10, 100),
+            _        => (50, 0, 50),
+        };
+        Toughness { acid, heat, impact }
+    }
+
+    pub fn radioactive(&self) -> Option<Radioactive> {
+        use Species::*;
+        match self {
+            Uranium   => Some(Radioactive { half_life: 200_000, product: Lead, warmth: 1, decay_heat: 100, neutrons: 1, fissile: false }),
+            Plutonium => Some(Radioactive { half_life: 20_000, product: Uranium, warmth: 3, decay_heat: 600, neutrons: 3, fissile: true }),
+            _ => None,
+        }
+    }
+
+    // most heat that passes between two touching cells in a frame. the
+    // poorer conductor of the two sets the pace.
+    pub fn conductivity(&self) -> i16 {
+        use Species::*;
+        match self {
+            Copper | MoltenCopper | Wire => 80,
+            Iron | MoltenIron => 40,
+            _ => 10,
+        }
+    }
+
+    // chance per frame of rusting a little, while wet. salt water is worse.
+    pub fn rusting(&self) -> f64 {
+        use Species::*;
+        match self {
+            Iron => 0.02,
+            Copper => 0.002,
+            _ => 0.0,
+        }
+    }
+
+    // how readily water seeps into it, given the chance. zero means it's
+    // watertight, so water pools on top of it instead.
+    pub fn permeability(&self) -> f64 {
+        use Species::*;
+        match self {
+            Sand   => 0.7,
+            Soil   => 0.35,
+            Mud(_) => 0.2,
+            _      => 0.0,
+        }
+    }
+
+    // how it piles up
+    pub fn granular(&self) -> Granular {
+        use Species::*;
+        let (friction, drop, reach) = match self {
+            Salt      => (0.0, 1, 2),
+            Ash       => (0.1, 1, 2),
+            Sand      => (0.2, 1, 1),
+            Gunpowder => (0.2, 1, 1),
+            Gravel    => (0.4, 2, 1),
+            // damp sand sticks together best, soaked mud starts to slump
+            Mud(wetness) if *wetness < Cell::MAX_WETNESS => (0.8, 4, 1),
+            Mud(_)    => (0.6, 2, 1),
+            _         => (0.0, 1, 1),
+        };
+        Granular { friction, drop, reach }
+    }
+
+    // what's left of it once it's worn down to nothing
+    pub fn debris(&self) -> Option<Species> {
+        use Species::*;
+        match self {
+            Stone | Basalt | Obsidian | Brick => Some(Gravel),
+            Glass => Some(Sand),
+            Iron | Copper => Some(Rust),
+            Gravel => Some(Sand),
+            _ => self.combustion().and_then(|c| c.residue),
+        }
+    }
+
+    // how much of a beating it shrugs off before it takes damage. for solids,
+    // this is also how many cells out they can hang unsupported.
+    pub fn strength(&self) -> u8 {
+        use Species::*;
+        match self {
+            Border | Wall | BlackHole | Repulsor => u8::MAX,
+            Clone(_) | Spout(_) | Drain | Void => 10,
+            Iron => 12,
+            Stone | Wire | Battery | Basalt | Copper => 8,
+            Obsidian | Brick => 6,
+            Glass => 4,
+            Wood | Trunk(_) | Branch(_) => 6,
+            Ice => 4,
+            Water | SaltWater(_) | MuddyWater(_) | Lava | Acid | Charcoal | Oil | Gasoline | Concrete(_, _) | MoltenGlass
+            | MoltenIron | MoltenCopper | MoltenSalt => 3,
+            Sand | Salt | Soil | Mud(_) | Ash | Gunpowder | Gravel | Clay | Rust | Uranium | Plutonium | Lead => 2,
+            Grass | GrassTip | Flower(_) | WaterGrass(_) | Nitro | Leaf | Seed | GrassSeed | Withered => 1,
+            _ => 0,
+        }
+    }
+}
+
+// empty cells count as air of this density.
+pub const AIR_DENSITY: f64 = 10.0;
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Gas {
+    // at room temperature. gases get lighter as they heat up.
+    pub density: f64,
+    // chance per frame of drifting into a random neighboring gas or air cell
+    pub diffusion: f64,
+    // chance per frame of bubbling up through liquid above it
+    pub bubble_rise: f64,
+}
+
+// damage taken per hit from each source, out of `MAX_DURABILITY`.
+// zero means it's immune.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Toughness {
+    pub acid: u8,
+    pub heat: u8,
+    pub impact: u8,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Radioactive {
+    // frames it takes for half of a pile to decay
+    pub half_life: u32,
+    // what it decays (or splits) into
+    pub product: Species,
+    // heat it gives off every frame just sitting there
+    pub warmth: i16,
+    // heat released when a nucleus goes
+    pub decay_heat: i16,
+    // neutrons thrown out when it goes
+    pub neutrons: u8,
+    // whether a neutron can split it
+    pub fissile: bool,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Liquid {
+    // relative to water. lighter liquids float on heavier ones.
+    pub density: f64,
+    // chance per frame of holding still rather than spreading sideways
+    pub viscosity: f64,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Conductor {
+    // heat given off each time a spark passes through
+    pub resistance: i16,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Granular {
+    // chance a grain holds its place rather than sliding down a slope
+    pub friction: f64,
+    // how far down the drop beside a grain has to go before it slides,
+    // and how far out it can land. together these set how steep its
+    // piles stand.
+    pub drop: i32,
+    pub reach: i32,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Attraction {
+    // chance of pulling a cell one step closer, at a distance of one cell.
+    // negative pushes instead.
+    pub strength: f32,
+    pub range: i32,
+    // whether it swallows what it pulls in
+    pub consumes: bool,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Explosive {
+    // heat it goes off at. it also goes off when touched by fire.
+    pub detonation: i16,
+    // blast radius, and how hard it hits at the center
+    pub power: i32,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Combustion {
+    pub ignition: i16,
+    // frames of burning a fresh cell is good for
+    pub fuel: Fuel,
+    // heat given off each frame while burning
+    pub heat_release: i16,
+    // chance per frame of puffing out smoke
+    pub smoke: f64,
+    // what's left once the fuel runs out
+    pub residue: Option<Species>,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct PhaseChange {
+    pub into: Species,
+    pub point: i16,
+    pub latent: i16,
+}
+
+impl PhaseChange {
+    const fn new(into: Species, point: i16, latent: i16) -> Self {
+        PhaseChange { into, point, latent }
+    }
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct Phases {
+    pub heated: Option<PhaseChange>,
+    pub cooled: Option<PhaseChange>,
 }
 
 
@@ -91,10 +548,27 @@ pub struct Cell {
     pub clock: bool,
     pub heat: i16,
     pub grain: u8,
+    pub fuel: Fuel,
+    pub durability: u8,
+    pub charge: u8,
+    pub falling: bool,
 }
 
 impl Cell {
     const MAX_WETNESS: u8 = 2;
+    const MAX_SALINITY: u8 = 8;
+    // how much salinity one grain of salt is worth
+    const SALT_PER_GRAIN: u8 = 4;
+    // grains of sediment a water cell can carry
+    const MAX_SEDIMENT: u8 = 3;
+    pub const MAX_DURABILITY: u8 = 100;
+    // charge of a freshly sparked conductor. it counts down from there, and
+    // can't be sparked again until it's back to zero.
+    pub const SPARK: u8 = 3;
+    // concrete is set once its cure gets here
+    pub const MAX_CURE: u8 = 100;
+    // water concrete can take on and still set
+    const MAX_MIX: u8 = 3;
 
     pub fn new(species: Species) -> Self {
         use Species::*;
@@ -107,6 +581,10 @@ impl Cell {
             clock: false,
             heat,
             grain: rng.gen(),
+            fuel: species.combustion().map_or(0, |c| c.fuel),
+            durability: Self::MAX_DURABILITY,
+            charge: 0,
+            falling: false,
         }
     }
 
@@ -120,42 +598,74 @@ impl Cell {
     
     pub fn is_solid(&self) -> bool {
         use Species::*;
-        matches!(self.species, Wall | Border | Stone | Ice)
+        matches!(self.species, Wall | Border | Stone | Ice | Wire | Battery | Wood | Trunk(_) | Branch(_)
+            | Glass | Basalt | Obsidian | Iron | Copper | Brick)
     }
 
-    pub fn is_gas(&self) -> bool {
+    // solids that can fall down if nothing holds them up
+    pub fn is_structural(&self) -> bool {
         use Species::*;
-        matches!(self.species, Steam)
+        self.is_solid() && !matches!(self.species, Wall | Border)
+    }
+
+    pub fn is_gas(&self) -> bool {
+        self.species.gas().is_some()
+    }
+
+    // density of a gas or air cell, if it is one.
+    pub fn gas_density(&self) -> Option<f64> {
+        if self.species == Species::Empty {
+            return Some(AIR_DENSITY)
+        }
+        let gas = self.species.gas()?;
+        Some(gas.density * 293.0 / (self.heat as f64 + 273.0).max(1.0))
     }
 
     pub fn is_liquid(&self) -> bool {
-        use Species::*;
-        matches!(self.species, Water | Acid | Lava | SaltWater)
+        self.species.liquid().is_some()
     }
 
     pub fn is_corrodable(&self) -> bool {
         use Species::*;
-        !self.is_gas() && !matches!(self.species, Empty | Wall | Border | Acid | Clone(_) )
+        !self.is_gas() && !matches!(self.species, Empty | Wall | Border | Acid | Clone(_) | BlackHole | Repulsor
+            | Spout(_) | Drain | Void )
     }
 
-    pub fn liquid_destroyable(&self) -> bool {
+    // loose enough to be pushed and pulled around
+    pub fn is_movable(&self) -> bool {
         use Species::*;
-        matches!(self.species, Grass | GrassTip | Flower(_))
+        !self.is_solid() && self.species.attraction().is_none()
+        && !matches!(self.species, Empty | Border | Clone(_) | Spout(_) | Drain | Void)
     }
 
-    pub fn turns_to_lava(&self) -> bool {
+    pub fn liquid_destroyable(&self) -> bool {
         use Species::*;
-        matches!(self.species, Sand | Mud(_) | Salt | Soil | Stone)
+        matches!(self.species, Grass | GrassTip | Flower(_))
     }
 
     pub fn is_flammable(&self) -> bool {
-        use Species::*;
-        matches!(self.species, Grass | GrassTip | Flower(_))
+        self.species.combustion().is_some()
+    }
+
+    // flammable liquids, which water can't put out
+    pub fn is_oily(&self) -> bool {
+        self.is_liquid() && self.is_flammable()
+    }
+
+    pub fn is_burning(&self) -> bool {
+        self.species.combustion().is_some_and(|c| self.heat >= c.ignition)
     }
 
     pub fn douses_fire(&self) -> bool {
         use Species::*;
-        !self.is_solid() && !self.is_flammable() && !matches!(self.species, Empty | Fire | BlueFire )
+        (!self.is_gas() || self.species == CarbonDioxide)
+        && !self.is_solid() && !self.is_flammable() && !matches!(self.species, Empty | Fire | BlueFire )
+    }
+
+    // hot enough to heat things across a gap
+    pub fn radiates(&self) -> bool {
+        use Species::*;
+        matches!(self.species, Lava | Fire | BlueFire | MoltenGlass | MoltenIron | MoltenCopper | MoltenSalt)
     }
 
     pub fn is_cold(&self) -> bool {
@@ -163,6 +673,28 @@ impl Cell {
         matches!(self.species, Ice)
     }
 
+    // knocks some durability off. returns true once it's worn through.
+    pub fn wear(&mut self, damage: u8) -> bool {
+        if damage == 0 {
+            return false
+        }
+        self.durability = self.durability.saturating_sub(damage);
+        self.durability == 0
+    }
+
+    // what takes this cell's place once it's worn through.
+    pub fn worn_out(&self) -> Cell {
+        match self.species.debris() {
+            Some(debris) => {
+                let mut cell = Cell::new(debris);
+                cell.heat = self.heat;
+                cell.clock = self.clock;
+                cell
+            }
+            None => EMPTY,
+        }
+    }
+
     // resets grain on cell. this is basically just for using the brush, 
     // so it doesn't look weird.
     pub fn regrain(&mut self) {
@@ -203,8 +735,349 @@ pub const EMPTY: Cell = Cell {
     clock: false,
     heat: 0,
     grain: 0,
+    fuel: 0,
+    durability: 0,
+    charge: 0,
+    falling: false,
 };
 
+// latent heat, in the same units as `Cell::heat`.
+const LATENT_VAPORIZATION: i16 = 540;
+const LATENT_FUSION: i16 = 80;
+const LATENT_ROCK_FUSION: i16 = 400;
+const LATENT_METAL_FUSION: i16 = 250;
+
+// a cell past its transition point spends the extra heat (or makes up the
+// missing heat) on changing phase, so it stays pinned at the transition point
+// until it has paid the whole latent cost. this is done by chance, so cells
+// don't need to remember how much they've paid: the odds of flipping are the
+// heat paid this frame over the total cost.
+fn pay_latent_heat(cell: &mut Cell, point: i16, latent: i16) -> bool {
+    let paid = (cell.heat - point).abs();
+    cell.heat = point;
+    thread_rng().gen_range(0..latent) < paid
+}
+
+// passes a spark on to any neighboring conductors that are ready for one.
+// batteries spark their neighbors every frame.
+pub fn conduct(api: &mut SandApi, cell: &mut Cell) -> Result<(), Error> {
+    let live = cell.species == Species::Battery || cell.charge == Cell::SPARK;
+    if live {
+        for n in api.neighbors()?.iter() {
+            let mut neighbor = n.cell;
+            if neighbor.charge == 0 && neighbor.species.conductor().is_some() {
+                neighbor.charge = Cell::SPARK;
+                api.set(n.dx, n.dy, neighbor)?;
+            }
+        }
+    }
+    if cell.charge > 0 {
+        cell.charge -= 1;
+        api.set(0, 0, *cell)?;
+    }
+    Ok(())
+}
+
+// moves a cell along the phase table if it's hot or cold enough.
+// returns true if it changed species.
+pub fn change_phase(api: &mut SandApi, cell: &mut Cell) -> Result<bool, Error> {
+    let phases = cell.species.phases();
+    let change = match (phases.heated, phases.cooled) {
+        (Some(change), _) if cell.heat > change.point => change,
+        (_, Some(change)) if cell.heat < change.point => change,
+        _ => return Ok(false),
+    };
+    if !pay_latent_heat(cell, change.point, change.latent) {
+        api.set(0, 0, *cell)?;
+        return Ok(false)
+    }
+    let salinity = match cell.species {
+        Species::SaltWater(salinity) => salinity,
+        _ => 0,
+    };
+    let sediment = match cell.species {
+        Species::MuddyWater(sediment) => sediment,
+        _ => 0,
+    };
+    cell.species = if cell.species == Species::Lava {
+        cooled_rock(api)?
+    } else {
+        change.into
+    };
+
+    // dissolved salt doesn't boil off with the water, or freeze into the ice.
+    // the liquid around takes it up, and whatever doesn't fit crystallizes out.
+    if salinity > 0 && !cell.is_liquid() {
+        let leftover = dissolve_salt(api, salinity)?;
+        if thread_rng().gen_range(0..Cell::SALT_PER_GRAIN) < leftover {
+            leave_behind(api, *cell, Species::Salt)?;
+            return Ok(true)
+        }
+    }
+
+    // nor does sediment. it goes to the water around, and the last grain
+    // settles out here. if the water around can't take the rest, it waits.
+    if sediment > 0 && !cell.is_liquid() {
+        let rest = shed_sediment(api, sediment - 1)?;
+        if rest > 0 {
+            cell.species = Species::MuddyWater(rest + 1);
+            api.set(0, 0, *cell)?;
+            return Ok(false)
+        }
+        leave_behind(api, *cell, Species::Sand)?;
+        return Ok(true)
+    }
+    api.set(0, 0, *cell)?;
+    Ok(true)
+}
+
+// leaves `residue` in the cell's place. the cell itself takes a free spot
+// nearby (preferring above) or is lost.
+fn leave_behind(api: &mut SandApi, cell: Cell, residue: Species) -> Result<(), Error> {
+    api.set(0, 0, Cell { species: residue, ..cell })?;
+    let mut neighbors = api.neighbors()?;
+    neighbors.sort_by_key(|n| n.dy);
+    if let Some(n) = neighbors.iter().find(|n| n.cell.species == Species::Empty) {
+        api.set(n.dx, n.dy, cell)?;
+    }
+    Ok(())
+}
+
+// hands grains of sediment to the water around, as far as it has room.
+// returns how many are left over.
+fn shed_sediment(api: &mut SandApi, mut load: Sediment) -> Result<Sediment, Error> {
+    for n in api.neighbors()?.iter_mut() {
+        if load == 0 {
+            break
+        }
+        let held = match n.cell.species {
+            Species::Water => 0,
+            Species::MuddyWater(held) => held,
+            _ => continue,
+        };
+        let taken = cmp::min(load, Cell::MAX_SEDIMENT.saturating_sub(held));
+        if taken > 0 {
+            n.cell.species = Species::MuddyWater(held + taken);
+            api.set(n.dx, n.dy, n.cell)?;
+            load -= taken;
+        }
+    }
+    Ok(load)
+}
+
+// what lava sets into depends on how fast it cooled. quenched by water it's
+// glassy obsidian, out in the open it's fine grained basalt, and only when
+// it's surrounded by hot rock does it cool slowly enough to be stone.
+fn cooled_rock(api: &SandApi) -> Result<Species, Error> {
+    use Species::*;
+    let neighbors = api.neighbors()?;
+    if neighbors.iter().any(|n| matches!(n.cell.species, Water | SaltWater(_) | MuddyWater(_) | Ice)) {
+        return Ok(Obsidian)
+    }
+    let cold = neighbors.iter().filter(|n| n.cell.heat < 600).count();
+    Ok(if cold >= 3 { Basalt } else { Stone })
+}
+
+// water, or salt water of a given salinity.
+fn brine(salinity: Salinity) -> Species {
+    if salinity == 0 {
+        Species::Water
+    } else {
+        Species::SaltWater(salinity)
+    }
+}
+
+// mixes salt into neighboring water, up to saturation.
+// returns how much didn't fit.
+fn dissolve_salt(api: &mut SandApi, mut salinity: Salinity) -> Result<Salinity, Error> {
+    let mut neighbors = api.neighbors()?;
+    neighbors.shuffle(&mut thread_rng());
+    for n in neighbors.iter_mut() {
+        let held = match n.cell.species {
+            Species::Water => 0,
+            Species::SaltWater(held) => held,
+            _ => continue,
+        };
+        let taken = cmp::min(salinity, Cell::MAX_SALINITY.saturating_sub(held));
+        if taken > 0 {
+            n.cell.species = brine(held + taken);
+            api.set(n.dx, n.dy, n.cell)?;
+            salinity -= taken;
+        }
+    }
+    Ok(salinity)
+}
+
+// flammable cells past their ignition point burn down their fuel, giving off
+// heat, flames and smoke, and turn into their residue once it's spent.
+// returns true if the cell burnt out.
+pub fn burn(api: &mut SandApi, cell: &mut Cell) -> Result<bool, Error> {
+    use Species::*;
+    let combustion = match cell.species.combustion() {
+        Some(c) if cell.heat >= c.ignition => c,
+        _ => return Ok(false),
+    };
+    let mut rng = thread_rng();
+
+    // smothered. water does nothing for burning oil, which just floats on it.
+    let smothered = api.neighbors()?.iter().any(|n| {
+        n.cell.douses_fire()
+        && !(cell.is_oily() && matches!(n.cell.species, Water | SaltWater(_) | MuddyWater(_)))
+    });
+    if smothered && rng.gen_bool(0.3) {
+        cell.heat = combustion.ignition - 1;
+        api.set(0, 0, *cell)?;
+        return Ok(false)
+    }
+
+    cell.fuel = cell.fuel.saturating_sub(1);
+    cell.heat = cell.heat.saturating_add(combustion.heat_release);
+    if cell.fuel == 0 {
+        match combustion.residue {
+            Some(residue) => {
+                let heat = cell.heat;
+                *cell = Cell::new(residue);
+                cell.heat = heat;
+                cell.clock = true;
+                api.set(0, 0, *cell)?;
+            }
+            None => api.set(0, 0, EMPTY)?,
+        }
+        return Ok(true)
+    }
+
+    // flames run across the surface of a burning liquid
+    if cell.is_liquid() {
+        for dx in [-1, 1].iter() {
+            let mut neighbor = api.get(*dx, 0)?;
+            if let Some(c) = neighbor.species.combustion() {
+                if neighbor.heat < c.ignition && rng.gen_bool(0.3) {
+                    neighbor.heat = c.ignition;
+                    api.set(*dx, 0, neighbor)?;
+                }
+            }
+        }
+    }
+
+    let mut dirs = [-1, 0, 1];
+    dirs.shuffle(&mut rng);
+    for dx in dirs.iter() {
+        if api.is_empty(*dx, -1) {
+            if rng.gen_bool(0.5) {
+                let mut flame = Cell::new(if cell.heat >= 3000 { BlueFire } else { Fire });
+                flame.heat = cmp::max(flame.heat, cell.heat);
+                api.set(*dx, -1, flame)?;
+            } else if rng.gen_bool(combustion.smoke) {
+                let mut smoke = Cell::new(Smoke);
+                smoke.heat = cell.heat;
+                api.set(*dx, -1, smoke)?;
+            }
+            break;
+        }
+    }
+    api.set(0, 0, *cell)?;
+    Ok(false)
+}
+
+// explosives go off once they're hot enough or touching fire.
+// returns true if the cell went off.
+pub fn detonate(api: &mut SandApi, cell: &mut Cell) -> Result<bool, Error> {
+    use Species::*;
+    let explosive = match cell.species.explosive() {
+        Some(e) => e,
+        None => return Ok(false),
+    };
+    let lit = cell.heat >= explosive.detonation
+        || api.neighbors()?.iter().any(|n| {
+            matches!(n.cell.species, Fire | BlueFire | Lava) || n.cell.is_burning()
+        });
+    if !lit {
+        return Ok(false)
+    }
+    blast(api, explosive.power)?;
+    Ok(true)
+}
+
+// a blast centered on the cursor. everything in range is heated, and either
+// destroyed or flung outward depending on its strength. explosives caught in
+// it are pushed past their detonation point, so they go off next.
+pub fn blast(api: &mut SandApi, power: i32) -> Result<(), Error> {
+    use Species::*;
+    let mut rng = thread_rng();
+    let (x, y) = api.get_cursor();
+
+    // outermost first, so there's room to push things into
+    let mut points = Vec::new();
+    for dx in -power..=power {
+        for dy in -power..=power {
+            let d = distance(0, 0, dx, dy);
+            if d <= power as f32 && (dx, dy) != (0, 0) {
+                points.push((dx, dy, d));
+            }
+        }
+    }
+    points.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+
+    for (dx, dy, d) in points {
+        api.set_cursor(x, y);
+        let force = power as f32 - d;
+        let mut target = api.get(dx, dy)?;
+        let mut fire = Cell::new(Fire);
+        fire.heat = fire.heat.saturating_add((force * 200.0) as i16);
+
+        if target.species == Border {
+            continue
+        } else if let Some(explosive) = target.species.explosive() {
+            target.heat = cmp::max(target.heat, explosive.detonation);
+            api.set(dx, dy, target)?;
+        } else if target.species == Empty {
+            if d < power as f32 / 2.0 && rng.gen_bool(0.5) {
+                api.set(dx, dy, fire)?;
+            }
+        } else if target.wear(impact_damage(&target, force)) {
+            api.set(dx, dy, if rng.gen_bool(0.3) { fire } else { target.worn_out() })?;
+        } else {
+            target.heat = target.heat.saturating_add((force * 100.0) as i16);
+            api.set(dx, dy, target)?;
+            if !target.is_solid() {
+                let push_x = (dx as f32 / d * force).round() as i32;
+                let push_y = (dy as f32 / d * force).round() as i32;
+                let (ox, oy) = api.orient(dx, dy);
+                api.set_cursor(x + ox, y + oy);
+                shove(api, push_x, push_y, target)?;
+            }
+        }
+    }
+
+    api.set_cursor(x, y);
+    let mut center = Cell::new(BlueFire);
+    center.heat = center.heat.saturating_add(power as i16 * 200);
+    api.set(0, 0, center)
+}
+
+// impacts weaker than a cell's strength glance off it.
+pub fn impact_damage(cell: &Cell, force: f32) -> u8 {
+    let excess = (force - cell.species.strength() as f32).max(0.0);
+    (excess * cell.species.toughness().impact as f32).min(255.0) as u8
+}
+
+// whether gravity gets a go at a loose cell this frame. when weightless,
+// the cell drifts about a little instead.
+fn feels_gravity(api: &mut SandApi, cell: Cell) -> Result<bool, Error> {
+    if api.gravity_pulls() {
+        return Ok(true)
+    }
+    let mut rng = thread_rng();
+    if api.is_weightless() && rng.gen_bool(0.05) {
+        let neighbors = api.neighbors()?;
+        let n = neighbors.choose(&mut rng).unwrap();
+        if n.cell.species == Empty {
+            api.swap(n.dx, n.dy, cell)?;
+        }
+    }
+    Ok(false)
+}
+
 fn go_toward(api: &mut SandApi, x: i32, y: i32, cell: Cell) -> Result<bool, Error> {
     // tries to go as far as possible towards the next point
     let path = line(0, 0, x, y);
@@ -228,6 +1101,28 @@ fn go_toward(api: &mut SandApi, x: i32, y: i32, cell: Cell) -> Result<bool, Erro
     Ok(moved)
 }
 
+// walks a cell outward one step at a time, stopping short of the first
+// thing in the way.
+fn shove(api: &mut SandApi, x: i32, y: i32, cell: Cell) -> Result<bool, Error> {
+    let steps = cmp::max(x.abs(), y.abs());
+    let mut to = None;
+    for i in 1..=steps {
+        let dx = (x as f32 * i as f32 / steps as f32).round() as i32;
+        let dy = (y as f32 * i as f32 / steps as f32).round() as i32;
+        if !api.is_empty(dx, dy) {
+            break
+        }
+        to = Some((dx, dy));
+    }
+    match to {
+        Some((dx, dy)) => {
+            api.swap(dx, dy, cell)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
 pub fn update_liquid(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
     let dirs = [1, 0, -1];
@@ -241,6 +1136,10 @@ pub fn update_liquid(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     fn can_swap(api: &mut SandApi, x: i32, y: i32) -> bool {
         api.is_empty(x, y) || api.get(x, y).unwrap().is_gas()
     }
+
+    if !feels_gravity(api, cell)? {
+        return Ok(())
+    }
     
     // fall down
     if rng.gen_bool(0.9) {
@@ -253,29 +1152,38 @@ pub fn update_liquid(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
         }
     }
 
+    // sink through lighter liquids
+    let density = cell.species.liquid().map_or(1.0, |l| l.density);
+    let lighter = |c: Cell| c.species.liquid().is_some_and(|l| l.density < density);
+    if lighter(api.get(0, 1)?) && rng.gen_bool(0.5) {
+        return api.swap(0, 1, cell)
+    }
+
+    let spreads = !rng.gen_bool(cell.species.liquid().map_or(0.0, |l| l.viscosity));
     if can_swap(api, 0, 1) {
         api.swap(0, 1, cell)?;
         return Ok(())
     } else if can_swap(api, dx, 1) {
         api.swap(dx, 1, cell)?;
         return Ok(())
-    } else if can_swap(api, dx, 0) {
+    } else if spreads && can_swap(api, dx, 0) {
         api.swap(dx, 0, cell)?;
         return Ok(())
     } else if can_swap(api, -dx, 1) {
         api.swap(-dx, 1, cell)?;
         return Ok(())
-    } else if can_swap(api, -dx, 0) {
+    } else if spreads && can_swap(api, -dx, 0) {
         api.swap(-dx, 0, cell)?;
         return Ok(())
     }
     
+    // mixes with liquids of the same weight
     let swap_probability = rng.gen::<u32>() % 100;
     if swap_probability < 10 {
         let mut neighbors = api.neighbors()?;
         neighbors.shuffle(&mut rng);
         for neighbor in neighbors.iter() {
-            if neighbor.cell.is_liquid() {
+            if neighbor.cell.species.liquid().is_some_and(|l| l.density == density) {
                 api.swap(neighbor.dx, neighbor.dy, cell)?;
                 break;
             }
@@ -287,16 +1195,47 @@ pub fn update_liquid(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
 
 pub fn update_gas(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
+    let (gas, density) = match (cell.species.gas(), cell.gas_density()) {
+        (Some(gas), Some(density)) => (gas, density),
+        _ => return Ok(()),
+    };
     let dirs = [-1, 0, 1];
     let dx = *dirs.choose(&mut rng).unwrap();
-    let dy = *dirs.choose(&mut rng).unwrap();
-    if api.get(dx, dy)?.is_gas() || api.is_empty(dx, dy) {
-        api.swap(dx, dy, cell)?;
+
+    if api.gravity_pulls() {
+        // bubble up through liquids
+        if api.get(dx, -1)?.is_liquid() && rng.gen_bool(gas.bubble_rise) {
+            return api.swap(dx, -1, cell)
+        }
+
+        // trade places with heavier gas above, or lighter gas below,
+        // the faster the bigger the difference
+        if let Some(above) = api.get(dx, -1)?.gas_density() {
+            if above > density && rng.gen_bool(1.0 - density / above) {
+                return api.swap(dx, -1, cell)
+            }
+        }
+        if let Some(below) = api.get(dx, 1)?.gas_density() {
+            if below < density && rng.gen_bool(1.0 - below / density) {
+                return api.swap(dx, 1, cell)
+            }
+        }
+    }
+
+    // mix
+    if rng.gen_bool(gas.diffusion) {
+        let dy = *dirs.choose(&mut rng).unwrap();
+        if api.get(dx, dy)?.gas_density().is_some() {
+            api.swap(dx, dy, cell)?;
+        }
     }
     Ok(())
 }
 
 pub fn update_coarse(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    if !feels_gravity(api, cell)? {
+        return Ok(())
+    }
     if let Ok(below) = api.get(0,1) {
         if below.is_fluid() {
             api.swap(0, 1, cell)?;
@@ -306,10 +1245,50 @@ pub fn update_coarse(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     Ok(())
 }
 
+pub fn update_solid(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
+    if api.is_supported(0, 0) {
+        return if cell.falling { land(api, cell) } else { Ok(()) }
+    }
+    if !feels_gravity(api, cell)? {
+        return Ok(())
+    }
+
+    // ice and wood float, so they only fall through air, and bob back up
+    // when they end up under water
+    let floats = matches!(cell.species, Species::Ice | Species::Wood);
+    if floats && api.get(0, -1)?.is_liquid() {
+        cell.falling = false;
+        return api.swap(0, -1, cell)
+    }
+    let below = api.get(0, 1)?;
+    if below.species == Species::Empty || below.is_gas() || (below.is_liquid() && !floats) {
+        cell.falling = true;
+        return api.swap(0, 1, cell)
+    }
+    if cell.falling && !below.is_liquid() {
+        return land(api, cell)
+    }
+    if cell.falling {
+        cell.falling = false;
+        api.set(0, 0, cell)?;
+    }
+    Ok(())
+}
+
+// a falling solid came to rest on something, and took a knock
+fn land(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
+    let mut rng = thread_rng();
+    cell.falling = false;
+    if rng.gen_bool(0.2) && cell.wear(cell.species.toughness().impact) {
+        return api.set(0, 0, cell.worn_out())
+    }
+    api.set(0, 0, cell)
+}
+
 pub fn update_powder(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
     let fall_probability = rng.gen::<u32>() % 100;
-    if fall_probability < 10 {
+    if fall_probability < 10 || !feels_gravity(api, cell)? {
         return Ok(()) // stay in place this frame
     }
 
@@ -329,15 +1308,25 @@ pub fn update_powder(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
         }
     } 
 
-    if let Ok(alternate) = api.get(dx, 1) {
-        if alternate.is_fluid() && alternate.species != Species::Lava {
-            api.swap(dx, 1, cell)?;
-            return Ok(())
-        }
-    } 
+    // otherwise slide down the slope, if it's steep enough
+    let granular = cell.species.granular();
+    if dx == 0 || rng.gen_bool(granular.friction) {
+        return Ok(())
+    }
+    let gives_way = |x: i32, y: i32| {
+        api.get(x, y).is_ok_and(|c| c.is_fluid() && c.species != Species::Lava)
+    };
+    let path_clear = (1..granular.reach).all(|i| gives_way(dx * i, 0));
+    let landing = dx * granular.reach;
+    if path_clear && (1..=granular.drop).all(|y| gives_way(landing, y)) {
+        api.swap(landing, 1, cell)?;
+    }
     Ok(())
 }
 
+// share of frames a wet cell gets to pass water on to each neighbor.
+const SEEP_RATE: f64 = 0.15;
+
 pub fn update_sand(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
 
     let mut rng = thread_rng();
@@ -351,8 +1340,7 @@ pub fn update_sand(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     let mut neighbors = api.neighbors()?;
     for neighbor in neighbors.iter_mut() {
         if let Water{ .. } = neighbor.cell.species { 
-            let absorb_probability = rng.gen::<u32>() % 100;
-            if absorb_probability < 10 && neighbor.dy < 0 {
+            if rng.gen_bool(SEEP_RATE * Sand.permeability()) && neighbor.dy < 0 {
                 api.set(neighbor.dx, neighbor.dy, EMPTY)?;
                 api.set(0, 0, Cell::mud())?; 
             }
@@ -367,8 +1355,13 @@ pub fn update_acid(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     update_liquid(api, cell)?;
     let mut rng = thread_rng();
     for neighbor in api.neighbors()?.iter() {
-        if rng.gen::<u32>() % 100 < 3 && neighbor.cell.is_corrodable() {
-            api.set(neighbor.dx, neighbor.dy, EMPTY)?;
+        let mut target = neighbor.cell;
+        if rng.gen::<u32>() % 100 < 3 && target.is_corrodable() {
+            if target.wear(target.species.toughness().acid) {
+                api.set(neighbor.dx, neighbor.dy, EMPTY)?;
+            } else {
+                api.set(neighbor.dx, neighbor.dy, target)?;
+            }
             if rng.gen::<u32>() % 100 < 30 {
                 api.set(0, 0, EMPTY)?;
             }
@@ -377,14 +1370,66 @@ pub fn update_acid(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     Ok(())
 }
 
+// flowing water picks up loose ground and very slowly wears down rock, and
+// drops what it's carrying again where it comes to rest. picked up grains are
+// swapped for water, and deposited grains take the place of the water
+// carrying them, so no grains are made or lost. sediment doesn't remember
+// what it was, though, so whatever's picked up settles out as sand.
+fn erode(api: &mut SandApi, cell: &mut Cell) -> Result<(), Error> {
+    use Species::*;
+    let load = match cell.species {
+        Water => 0,
+        MuddyWater(load) => load,
+        _ => return Ok(()),
+    };
+    let mut rng = thread_rng();
+    let flowing = [(0, 1), (-1, 1), (1, 1), (-1, 0), (1, 0)]
+        .iter()
+        .any(|&(dx, dy)| api.is_empty(dx, dy));
+
+    if flowing {
+        let neighbors = api.neighbors()?;
+        let n = neighbors.choose(&mut rng).unwrap();
+        let mut ground = n.cell;
+        match ground.species {
+            Sand | Soil | Ash if load < Cell::MAX_SEDIMENT && n.dy >= 0 && rng.gen_bool(0.02) => {
+                api.set(n.dx, n.dy, Cell::new(Water))?;
+                cell.species = MuddyWater(load + 1);
+                api.set(0, 0, *cell)?;
+            }
+            Stone | Gravel if rng.gen_bool(0.01) => {
+                if ground.wear(1) {
+                    ground = ground.worn_out();
+                }
+                api.set(n.dx, n.dy, ground)?;
+            }
+            _ => {}
+        }
+    } else if load > 0 && rng.gen_bool(0.01 * load as f64) {
+        // settles, handing whatever else it carried to the water next to it.
+        // if there's no room there, it holds on to it for now.
+        let rest = shed_sediment(api, load - 1)?;
+        if rest > 0 {
+            cell.species = MuddyWater(rest + 1);
+            return api.set(0, 0, *cell)
+        }
+        let heat = cell.heat;
+        *cell = Cell::new(Sand);
+        cell.heat = heat;
+        cell.clock = true;
+        api.set(0, 0, *cell)?;
+    }
+    Ok(())
+}
+
 pub fn update_water(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
-    if cell.heat >= 100 {
-        cell.species = Species::Steam;
-        return api.set(0, 0, cell)
-    } else if cell.heat <= 0 {
-        cell.species = Species::Ice;
-        return api.set(0, 0, cell)
+    erode(api, &mut cell)?;
+    if !cell.is_liquid() {
+        return Ok(())
+    }
+    if api.is_weightless() {
+        return update_liquid(api, cell)
     }
     // ride underneath surfaces
     let mut dirs = [1, -1];
@@ -432,10 +1477,16 @@ pub fn update_mud(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
         }
     }
 
-    for neighbor in neighbors.iter_mut() {
-        let absorb_probability = rng.gen::<u32>() % 100;
+    // water sinks through porous ground, and only spreads sideways once
+    // there's nowhere further down for it to go
+    let can_sink = match api.get(0, 1)?.species {
+        Mud(wetness) => wetness < max_wetness,
+        Empty => true,
+        below => below.permeability() > 0.0,
+    };
 
-        if absorb_probability > 15 {
+    for neighbor in neighbors.iter_mut() {
+        if !rng.gen_bool(SEEP_RATE) {
             continue
         }
 
@@ -443,8 +1494,10 @@ pub fn update_mud(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
             let own_wetness = wetness;
             match neighbor.cell.species { 
 
-                Sand => {
-                    if own_wetness >= 1 && neighbor.dy >= 0 {
+                Sand | Soil => {
+                    if own_wetness >= 1 
+                    && neighbor.dy >= 0 
+                    && rng.gen_bool(neighbor.cell.species.permeability()) {
                         api.set(neighbor.dx, neighbor.dy, Cell::mud())?;
                         cell.species = Mud(own_wetness - 1);
                         api.set(0, 0, cell)?;
@@ -455,9 +1508,9 @@ pub fn update_mud(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
                     let neighbor_wetness = wetness;
                     if neighbor_wetness < max_wetness 
                     && own_wetness >= 1 
-                    && neighbor.dy >= 0
+                    && (neighbor.dy > 0 || (neighbor.dy == 0 && !can_sink))
                     && neighbor.dy.abs() != neighbor.dx.abs() 
-                    && rng.gen::<u32>() % 100 < 20 {
+                    && rng.gen_bool(neighbor.cell.species.permeability()) {
                         neighbor.cell.species = Mud (neighbor_wetness + 1);
                         api.set(neighbor.dx, neighbor.dy, neighbor.cell)?;
                         cell.species = Mud (own_wetness - 1 );
@@ -466,10 +1519,8 @@ pub fn update_mud(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
                 }
 
                 Water => {
-                    if own_wetness < max_wetness && rng.gen_bool(0.2) {
-                        if rng.gen_bool(0.02) {
-                            api.set(neighbor.dx, neighbor.dy, EMPTY)?;
-                        }
+                    if own_wetness < max_wetness && rng.gen_bool(cell.species.permeability()) {
+                        api.set(neighbor.dx, neighbor.dy, EMPTY)?;
                         cell.species = Mud (own_wetness + 1 );
                         api.set(0, 0, cell)?;
                     } else if neighbor.dy == -1 
@@ -481,8 +1532,10 @@ pub fn update_mud(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
                 }
 
                 Empty => {
+                    // drains mostly out of the bottom
+                    let drain = if neighbor.dy > 0 { 0.5 } else { 0.1 };
                     if own_wetness >= 1 
-                    && neighbor.dy >= 0 && rng.gen::<u32>() % 100 < 10 {
+                    && neighbor.dy >= 0 && rng.gen_bool(drain * cell.species.permeability()) {
                         cell.species = Mud (own_wetness - 1 );
                         api.set(neighbor.dx, neighbor.dy, Cell::new(Species::Water))?;
                         api.set(0, 0, cell)?;
@@ -497,26 +1550,139 @@ pub fn update_mud(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
             }
         }
     }     
-    update_coarse(api, cell)?;
+    update_powder(api, cell)?;
  
    Ok(())
 }
 
+// nothing overhead but air, gas and water, all the way up. the map clamps
+// reads past its edge, so without borders this stops at the map's size.
+fn sunlit(api: &SandApi) -> bool {
+    use Species::*;
+    for dy in 1..cmp::max(api.width, api.height) {
+        match api.get(0, -dy) {
+            Ok(c) if c.species == Empty || c.is_gas() || matches!(c.species, Water | SaltWater(_) | Glass) => continue,
+            Ok(c) => return c.species == Border,
+            Err(_) => return true,
+        }
+    }
+    true
+}
+
+// where the plant this cell belongs to can get water from: mud or water
+// around the ground it's rooted in.
+fn water_source(api: &SandApi) -> Result<Option<(i32, i32)>, Error> {
+    use Species::*;
+    let mut dy = 0;
+    while matches!(api.get(0, dy)?.species, Grass | GrassTip | Flower(_) | GrassSeed | Seed) {
+        dy += 1;
+        if dy >= cmp::max(api.width, api.height) {
+            return Ok(None)
+        }
+    }
+    root_water(api, 0, dy)
+}
+
+// water around the ground at `(x, y)`, if a plant could root there
+fn root_water(api: &SandApi, x: i32, y: i32) -> Result<Option<(i32, i32)>, Error> {
+    use Species::*;
+    match api.get(x, y)?.species {
+        Mud(_) => return Ok(Some((x, y))),
+        Soil => {}
+        _ => return Ok(None),
+    }
+    for (dx, dy) in [(-1, 0), (1, 0), (0, 1), (-1, 1), (1, 1)].iter() {
+        if matches!(api.get(x + dx, y + dy)?.species, Mud(_) | Water) {
+            return Ok(Some((x + dx, y + dy)))
+        }
+    }
+    Ok(None)
+}
+
+// most wood searched for a tree's roots
+const MAX_TREE_SEARCH: usize = 128;
+
+// like `water_source`, for a part of a tree. follows the wood it's attached
+// to until it finds where the tree's rooted.
+fn tree_water(api: &SandApi) -> Result<Option<(i32, i32)>, Error> {
+    use Species::*;
+    let mut seen = vec![(0, 0)];
+    let mut queue = VecDeque::from(vec![(0, 0)]);
+    while let Some((x, y)) = queue.pop_front() {
+        if let Some(source) = root_water(api, x, y + 1)? {
+            return Ok(Some(source))
+        }
+        for (dx, dy) in [(0, 1), (-1, 1), (1, 1), (-1, 0), (1, 0), (-1, -1), (0, -1), (1, -1)].iter() {
+            let (nx, ny) = (x + dx, y + dy);
+            if seen.len() < MAX_TREE_SEARCH
+            && !seen.contains(&(nx, ny))
+            && matches!(api.get(nx, ny)?.species, Wood | Trunk(_) | Branch(_)) {
+                seen.push((nx, ny));
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn find_water(api: &SandApi, cell: Cell) -> Result<Option<(i32, i32)>, Error> {
+    use Species::*;
+    match cell.species {
+        Trunk(_) | Branch(_) | Leaf => tree_water(api),
+        _ => water_source(api),
+    }
+}
+
+// a plant takes up some of the water at `(dx, dy)`
+fn drink(api: &mut SandApi, dx: i32, dy: i32) -> Result<(), Error> {
+    use Species::*;
+    let mut source = api.get(dx, dy)?;
+    source.species = match source.species {
+        Mud(wetness) if wetness > 0 => Mud(wetness - 1),
+        Mud(_) => Soil,
+        Water => return api.set(dx, dy, EMPTY),
+        _ => return Ok(()),
+    };
+    api.set(dx, dy, source)
+}
+
+// starved of water or light for too long, a plant withers. a growing tree
+// tip just stops, and stays as wood.
+fn wither(api: &mut SandApi, cell: Cell) -> Result<bool, Error> {
+    use Species::*;
+    let mut rng = thread_rng();
+    if !rng.gen_bool(0.01) {
+        return Ok(false)
+    }
+    let starved = find_water(api, cell)?.is_none() || (!sunlit(api) && rng.gen_bool(0.2));
+    if starved {
+        let species = match cell.species {
+            Trunk(_) | Branch(_) => Wood,
+            _ => Withered,
+        };
+        api.set(0, 0, Cell { species, ..cell })?;
+    }
+    Ok(starved)
+}
+
 pub fn update_soil(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     update_coarse(api, cell)?;
     let mut rng = thread_rng();
     if api.is_empty(0, -1) 
-    && rng.gen::<u32>() % 100 < 1 {
+    && rng.gen::<u32>() % 100 < 1 
+    && water_source(api)?.is_some()
+    && sunlit(api) {
         api.set(0, -1, Cell::new(Species::Grass))?; 
     }
     // occasionally absorb water, become mud
     use Species::*;
     let mut neighbors = api.neighbors()?;
     let absorb_probability = rng.gen::<u32>() % 1000;
+    let soaks = rng.gen_bool(SEEP_RATE * Soil.permeability());
     for neighbor in neighbors.iter_mut() {
         match neighbor.cell.species {
             Water => { 
-                if absorb_probability < 55 && neighbor.dy < 0 {
+                if soaks && neighbor.dy < 0 {
                     api.set(neighbor.dx, neighbor.dy, EMPTY)?;
                     api.set(0, 0, Cell::mud())?; 
                 }
@@ -537,13 +1703,18 @@ pub fn update_soil(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
 
 pub fn update_grass(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
+    if wither(api, cell)? {
+        return Ok(())
+    }
 
     let root = api.get(0, 1)?.species;
-    if root != Species::Soil && root != Species::Grass && rng.gen_bool(0.02) {
+    if !matches!(root, Species::Soil | Species::Mud(_) | Species::Grass) && rng.gen_bool(0.02) {
         api.set(0,0,EMPTY)?;
 
-    } else {
-        if api.is_empty(0, -1) {
+    } else if api.is_empty(0, -1) && rng.gen_bool(0.1) && sunlit(api) {
+        // growing takes water
+        if let Some((dx, dy)) = water_source(api)? {
+            drink(api, dx, dy)?;
             if rng.gen::<u32>() % 100 < 75 {
                 api.set(0, -1, Cell::new(Species::Grass))?;
             } else {
@@ -558,6 +1729,9 @@ pub fn update_grass(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
 
 pub fn update_grass_tip(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
+    if wither(api, cell)? {
+        return Ok(())
+    }
     update_coarse(api, cell)?;
     let bloom_probability = rng.gen::<u32>() % 1000;
     if bloom_probability < 1 {
@@ -578,6 +1752,18 @@ pub fn update_grass_tip(api: &mut SandApi, mut cell: Cell) -> Result<(), Error>
 }
 
 pub fn update_flower(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    let mut rng = thread_rng();
+    // the petals go with the head
+    if api.get(0, 1)?.species == Species::Grass && wither(api, cell)? {
+        return Ok(())
+    }
+
+    // scatters seeds
+    let dx = *[-1, 1].choose(&mut rng).unwrap();
+    if api.is_empty(dx, 0) && rng.gen_bool(0.002) {
+        api.set(dx, 0, Cell::new(Species::GrassSeed))?;
+    }
+
     // grow from "grass"
     if api.get(0, 1)?.species == Species::Grass
     && [
@@ -601,6 +1787,211 @@ pub fn update_flower(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     Ok(())
 }
 
+pub fn update_grass_seed(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    let mut rng = thread_rng();
+    // takes root in damp, sunny ground
+    if rng.gen_bool(0.01)
+    && matches!(api.get(0, 1)?.species, Species::Soil | Species::Mud(_))
+    && water_source(api)?.is_some()
+    && sunlit(api) {
+        return api.set(0, 0, Cell::new(Species::Grass))
+    }
+    update_powder(api, cell)
+}
+
+pub fn update_withered(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    use Species::*;
+    let mut rng = thread_rng();
+    // rots into the ground it's lying on
+    if matches!(api.get(0, 1)?.species, Soil | Mud(_) | Sand | Withered) && rng.gen_bool(0.002) {
+        return api.set(0, 0, Cell::new(Soil))
+    }
+    update_coarse(api, cell)
+}
+
+// pours like a thick liquid, and sets into stone over `SandApi::cure_time`
+// frames at room temperature. heat speeds it up, cold stops it, and if it's
+// been watered down too much it never sets at all.
+pub fn update_concrete(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
+    let mut rng = thread_rng();
+    let (cure, water) = match cell.species {
+        Species::Concrete(cure, water) => (cure, water),
+        _ => return Ok(()),
+    };
+
+    if water <= Cell::MAX_MIX {
+        if let Some(n) = api.neighbors()?.iter().find(|n| n.cell.species == Species::Water) {
+            if rng.gen_bool(0.05) {
+                api.set(n.dx, n.dy, EMPTY)?;
+                cell.species = Species::Concrete(cure, water + 1);
+                return api.set(0, 0, cell)
+            }
+        }
+    }
+
+    let warmth = ((cell.heat as f64 + 10.0) / 30.0).clamp(0.0, 4.0);
+    let rate = (Cell::MAX_CURE as f64 / api.cure_time().max(1) as f64 * warmth).min(1.0);
+    if water <= Cell::MAX_MIX && rng.gen_bool(rate) {
+        if cure + 1 >= Cell::MAX_CURE {
+            return api.set(0, 0, Cell { species: Species::Stone, ..cell })
+        }
+        cell.species = Species::Concrete(cure + 1, water);
+        api.set(0, 0, cell)?;
+    }
+    update_liquid(api, cell)
+}
+
+// wet metal slowly rusts through, crumbling into rust once it's gone
+pub fn update_metal(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
+    use Species::*;
+    let mut rng = thread_rng();
+    let rusting = cell.species.rusting();
+    let wet = api.neighbors()?.iter().map(|n| match n.cell.species {
+        Water | MuddyWater(_) | Mud(_) => rusting,
+        SaltWater(_) => rusting * 4.0,
+        _ => 0.0,
+    }).fold(0.0, f64::max);
+    if wet > 0.0 && rng.gen_bool(wet.min(1.0)) {
+        if cell.wear(1) {
+            return api.set(0, 0, cell.worn_out())
+        }
+        api.set(0, 0, cell)?;
+    }
+    update_solid(api, cell)
+}
+
+// a neutron that lands on fissile material splits it this often
+const NEUTRON_CAPTURE: f64 = 0.5;
+// how far a neutron gets
+const NEUTRON_RANGE: i32 = 3;
+// most nuclei one chain reaction gets through in a frame
+const MAX_SPLITS: usize = 32;
+
+pub fn update_radioactive(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    let radioactive = match cell.species.radioactive() {
+        Some(r) => r,
+        None => return Ok(()),
+    };
+    let decay = 1.0 - 0.5f64.powf(1.0 / radioactive.half_life as f64);
+    if thread_rng().gen_bool(decay) {
+        return split(api, 0, 0)
+    }
+    update_powder(api, cell)
+}
+
+// the nucleus at `(dx, dy)` goes, throwing out neutrons that can split
+// fissile material around it, which throws out more. packed tightly enough,
+// each split sets off more than one other and it runs away.
+fn split(api: &mut SandApi, dx: i32, dy: i32) -> Result<(), Error> {
+    let mut rng = thread_rng();
+    let mut pending = vec![(dx, dy)];
+    let mut splits = 0;
+    while let Some((x, y)) = pending.pop() {
+        let mut cell = api.get(x, y)?;
+        // only fissile material is split by a neutron, and a spot can be hit
+        // more than once, so it may already have gone
+        let radioactive = match cell.species.radioactive() {
+            Some(r) if r.fissile || splits == 0 => r,
+            _ => continue,
+        };
+        cell.species = radioactive.product;
+        cell.heat = cell.heat.saturating_add(radioactive.decay_heat);
+        api.set(x, y, cell)?;
+
+        splits += 1;
+        if splits >= MAX_SPLITS {
+            break
+        }
+        for _ in 0..radioactive.neutrons {
+            let nx = x + rng.gen_range(-NEUTRON_RANGE..=NEUTRON_RANGE);
+            let ny = y + rng.gen_range(-NEUTRON_RANGE..=NEUTRON_RANGE);
+            let fissile = api.get(nx, ny).is_ok_and(|c| c.species.radioactive().is_some_and(|r| r.fissile));
+            if fissile && rng.gen_bool(NEUTRON_CAPTURE) {
+                pending.push((nx, ny));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn update_seed(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    let mut rng = thread_rng();
+    // sprouts once it's settled on damp, sunny soil
+    if matches!(api.get(0, 1)?.species, Species::Soil | Species::Mud(_) | Species::Grass)
+    && rng.gen_bool(0.01)
+    && water_source(api)?.is_some()
+    && sunlit(api) {
+        return api.set(0, 0, Cell::new(Species::Trunk(rng.gen_range(12..24))))
+    }
+    update_powder(api, cell)
+}
+
+// a trunk or branch tip. it grows a step at a time, leaving wood behind,
+// and bursts into leaves when it's done.
+pub fn update_tree(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    use Species::*;
+    let mut rng = thread_rng();
+    if wither(api, cell)? {
+        return Ok(())
+    }
+    if !rng.gen_bool(0.1) || !sunlit(api) {
+        return update_solid(api, cell)
+    }
+    // growing takes water, drawn up from the roots
+    match find_water(api, cell)? {
+        Some((dx, dy)) => drink(api, dx, dy)?,
+        None => return update_solid(api, cell),
+    }
+
+    let (left, height) = match cell.species {
+        Trunk(left) => (left, left),
+        Branch(left) => (left, 0),
+        _ => return Ok(()),
+    };
+    // branches grow away from the wood they sprouted from
+    let away = if api.get(-1, 0)?.species == Wood || api.get(-1, 1)?.species == Wood { 1 } else { -1 };
+    let (dx, dy) = match cell.species {
+        Trunk(_) => (0, -1),
+        _ => (away, *[-1, 0].choose(&mut rng).unwrap()),
+    };
+
+    let wood = Cell { species: Wood, ..cell };
+    if left == 0 || !api.is_empty(dx, dy) {
+        for n in api.neighbors()?.iter() {
+            if n.cell.species == Empty {
+                api.set(n.dx, n.dy, Cell::new(Leaf))?;
+            }
+        }
+        return api.set(0, 0, wood)
+    }
+
+    let tip = match cell.species {
+        Trunk(_) => Trunk(left - 1),
+        _ => Branch(left - 1),
+    };
+    api.set(dx, dy, Cell { species: tip, ..cell })?;
+    api.set(0, 0, wood)?;
+
+    // the upper part of a trunk puts out branches
+    let side = *[-1, 1].choose(&mut rng).unwrap();
+    if height > 2 && height < 10 && rng.gen_bool(0.3) && api.is_empty(side, 0) {
+        api.set(side, 0, Cell::new(Branch(cmp::min(height / 2, 5))))?;
+    }
+    Ok(())
+}
+
+pub fn update_leaf(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    use Species::*;
+    if wither(api, cell)? {
+        return Ok(())
+    }
+    // held up by the tree, and falls once it's gone
+    if api.neighbors()?.iter().any(|n| matches!(n.cell.species, Wood | Trunk(_) | Branch(_))) {
+        return Ok(())
+    }
+    update_coarse(api, cell)
+}
+
 pub fn update_water_grass(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
     let max_height = 30;
     let mut rng = thread_rng();
@@ -647,10 +2038,6 @@ pub fn update_water_grass(api: &mut SandApi, mut cell: Cell) -> Result<(), Error
 
 pub fn update_lava(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
-    if cell.heat < 1600 && rng.gen::<u32>() % 100 < 1 {
-        cell.species = Species::Stone;
-        return api.set(0, 0, cell);
-    }
     for n in api.neighbors()?.iter_mut() {
         if n.cell.heat < cell.heat && n.cell != EMPTY {
             n.cell.heat = cmp::min(n.cell.heat + 30, cell.heat);
@@ -670,51 +2057,45 @@ pub fn update_lava(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
 
 pub fn update_steam(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
-    let dx = *[1, 0, 0, -1].choose(&mut rng).unwrap();
     
     if rng.gen::<u32>() % 1000 < 5 {
         return Ok(())
     }
 
-    if cell.heat < 100 && rng.gen_bool(0.1) {
-        if rng.gen_bool(0.6) {
-            cell.species = Species::Water;
-            api.set(0, 0, cell)?; 
-            return Ok(())
-        } else {
-            api.set(0, 0, EMPTY)?;
-            return Ok(())
-        } 
-    } 
-
-    if rng.gen::<i16>() % 100 < (70 - (cell.heat - 100))  {
-        let dy = *[1, 0, 0, 0, 0, 0, -1, -1].choose(&mut rng).unwrap();
-        if api.is_empty(dx, dy) {
-            api.swap(dx, dy, cell)?;
-        }
-        return Ok(())
+    // loses heat to whatever is capping it
+    let above = api.get(0, -1)?;
+    if above.species != Species::Empty && !above.is_gas() && rng.gen_bool(0.5) {
+        cell.heat -= 10;
+        api.set(0, 0, cell)?;
     }
+    update_gas(api, cell)
+}
 
-    if api.is_empty(dx, -1) || api.get(dx, -1)?.is_gas() {
-        api.swap(dx, -1, cell)?;
-        return Ok(());
-    }  
+pub fn update_smoke(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    let mut rng = thread_rng();
 
-    if rng.gen::<u32>() % 100 < 50 {
-        cell.heat -= 10;
+    // thins out
+    if rng.gen::<u32>() % 1000 < 4 {
+        return api.set(0, 0, EMPTY)
     }
     update_gas(api, cell)
 }
 
-pub fn update_salt(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
+pub fn update_salt(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
     let mut rng = thread_rng();
     let mut neighbors = api.neighbors()?;
     neighbors.shuffle(&mut rng);
     for n in neighbors.iter_mut() {
-        if n.cell.species == Species::Water && rng.gen::<u32>() % 100 < 5 {
-            cell.species = Species::SaltWater;
-            api.set(n.dx, n.dy, EMPTY)?;
-            api.set(0, 0, cell)?;
+        let held = match n.cell.species {
+            Species::Water => 0,
+            Species::SaltWater(held) => held,
+            _ => continue,
+        };
+        // dissolves, unless the water's saturated
+        if held + Cell::SALT_PER_GRAIN <= Cell::MAX_SALINITY && rng.gen::<u32>() % 100 < 5 {
+            n.cell.species = brine(held + Cell::SALT_PER_GRAIN);
+            api.set(n.dx, n.dy, n.cell)?;
+            api.set(0, 0, EMPTY)?;
             return Ok(())
         }
     }
@@ -722,12 +2103,24 @@ pub fn update_salt(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
 }
 
 pub fn update_salt_water(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
-    if cell.heat >= 101 {
-        cell.species = Species::Steam;
-        api.set(0, -1, cell)?;
-        cell.species = Species::Salt;
-        api.set(0, 0, cell)?; 
-        return Ok(())
+    let mut rng = thread_rng();
+
+    // salt spreads out into less salty water
+    if let Species::SaltWater(salinity) = cell.species {
+        let neighbors = api.neighbors()?;
+        let n = neighbors.choose(&mut rng).unwrap();
+        let mut neighbor = n.cell;
+        let held = match neighbor.species {
+            Species::Water => Some(0),
+            Species::SaltWater(held) => Some(held),
+            _ => None,
+        };
+        if let Some(held) = held.filter(|held| salinity > held + 1) {
+            neighbor.species = brine(held + 1);
+            api.set(n.dx, n.dy, neighbor)?;
+            cell.species = brine(salinity - 1);
+            api.set(0, 0, cell)?;
+        }
     }
     update_liquid(api, cell)
 }
@@ -740,6 +2133,10 @@ pub fn update_fire(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
         cell.species = Fire;
     }
     if cell.heat < 600 {
+        if rng.gen_bool(0.2) {
+            cell.species = Smoke;
+            return api.set(0, 0, cell)
+        }
         api.set(0, 0, EMPTY)?;
         return Ok(());
     }
@@ -760,11 +2157,13 @@ pub fn update_fire(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
         }
     }
 
-    for mut n in api.neighbors()?.iter_mut().filter(|n| n.cell.is_flammable()) {
-        if matches!(n.cell.species, Fire | BlueFire | Lava) && rng.gen_bool(0.2) {
-            n.cell.species = Fire;
-            n.cell.heat = 800;
-            api.set(n.dx, n.dy, n.cell)?;
+    // catch flammable neighbors
+    for n in api.neighbors()?.iter_mut() {
+        if let Some(combustion) = n.cell.species.combustion() {
+            if n.cell.heat < combustion.ignition && rng.gen_bool(0.2) {
+                n.cell.heat = combustion.ignition;
+                api.set(n.dx, n.dy, n.cell)?;
+            }
         }
     }
 
@@ -804,20 +2203,26 @@ pub fn update_clone(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
     if let Clone(contents) = cell.species {
         if contents == None {
             for n in neighbors.iter() {
-                if !matches!(n.cell.species, Clone(_) | Empty | Border) {
-                    cell.species = Clone(api.store_cloned_cell(n.cell.species));
+                if !matches!(n.cell.species, Clone(_) | Spout(_) | Empty | Border) {
+                    let mut template = n.cell;
+                    template.clock = false;
+                    template.charge = 0;
+                    cell.species = Clone(api.store_cloned_cell(template));
                     api.set(0, 0, cell)?;
                     break;
                 }
             }
         }
         if let Some(id) = contents {
-            let cloned = api.get_cloned_cell(id);
-            cell.heat = cloned.species.starting_temp();
+            let template = api.get_cloned_cell(id);
+            cell.heat = template.cell.heat;
             for n in api.neighbors()?.iter() {
                 let mut neighbor = n.cell;
-                if n.cell == EMPTY && rng.gen_bool(0.05) {
-                    api.set(n.dx, n.dy, cloned)?;
+                let (dx, dy) = api.orient(n.dx, n.dy);
+                if n.cell == EMPTY && template.emission.allows(dx, dy) && rng.gen_bool(template.emission.rate) {
+                    let mut copy = template.cell;
+                    copy.regrain();
+                    api.set(n.dx, n.dy, copy)?;
                 } else if neighbor.species == Clone(None) && rng.gen_bool(0.1) {
                     neighbor.species = cell.species;
                     api.set(n.dx, n.dy, neighbor)?;
@@ -829,10 +2234,85 @@ pub fn update_clone(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
     Ok(())
 }
 
-pub fn update_ice(api: &mut SandApi, mut cell: Cell) -> Result<(), Error> {
-    if cell.heat > 0 {
-        cell.species = Species::Water;
-        api.set(0, 0, cell)?;
+pub fn update_spout(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    let spout = match cell.species {
+        Species::Spout(Some(id)) => match api.get_spout(id) {
+            Some(spout) => spout,
+            None => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    let (x, y) = api.get_cursor();
+    let (tx, ty) = (x + spout.dx, y + spout.dy);
+    if api.get_absolute(tx, ty).is_ok_and(|c| c == EMPTY) && thread_rng().gen_bool(spout.rate) {
+        api.set_absolute(tx, ty, Cell::new(spout.species))?;
+    }
+    Ok(())
+}
+
+// swallows any liquid that touches it
+pub fn update_drain(api: &mut SandApi, _cell: Cell) -> Result<(), Error> {
+    for n in api.neighbors()?.iter() {
+        if n.cell.is_liquid() {
+            api.set(n.dx, n.dy, EMPTY)?;
+        }
+    }
+    Ok(())
+}
+
+// deletes anything that touches it
+pub fn update_void(api: &mut SandApi, _cell: Cell) -> Result<(), Error> {
+    use Species::*;
+    for n in api.neighbors()?.iter() {
+        if !matches!(n.cell.species, Empty | Border | Void) {
+            api.set(n.dx, n.dy, EMPTY)?;
+        }
+    }
+    Ok(())
+}
+
+// pulls (or pushes) loose cells around it, a step at a time. it looks at a
+// handful of random spots in range each frame, and the odds of moving what's
+// there drop off with distance.
+pub fn update_attractor(api: &mut SandApi, cell: Cell) -> Result<(), Error> {
+    let attraction = match cell.species.attraction() {
+        Some(attraction) => attraction,
+        None => return Ok(()),
+    };
+    let mut rng = thread_rng();
+    let (x, y) = api.get_cursor();
+    let range = attraction.range;
+
+    for _ in 0..32 {
+        let dx = rng.gen_range(-range..=range);
+        let dy = rng.gen_range(-range..=range);
+        let d = distance(0, 0, dx, dy);
+        if d > range as f32 || (dx, dy) == (0, 0) {
+            continue
+        }
+        let target = api.get(dx, dy)?;
+        if !target.is_movable() {
+            continue
+        }
+        let pull = attraction.strength / d;
+        if !rng.gen_bool(pull.abs().min(1.0) as f64) {
+            continue
+        }
+        if attraction.consumes && d < 1.5 {
+            api.set(dx, dy, EMPTY)?;
+            continue
+        }
+        let (step_x, step_y) = if pull > 0.0 {
+            (-dx.signum(), -dy.signum())
+        } else {
+            (dx.signum(), dy.signum())
+        };
+        let (ox, oy) = api.orient(dx, dy);
+        api.set_cursor(x + ox, y + oy);
+        if api.is_empty(step_x, step_y) {
+            api.swap(step_x, step_y, target)?;
+        }
+        api.set_cursor(x, y);
     }
     Ok(())
 }
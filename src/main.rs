@@ -8,12 +8,13 @@ pub mod map2d;
 use generational_arena::Arena;
 use sdl2::pixels::Color;
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
+use sdl2::keyboard::{Keycode, Mod};
 use sdl2::render::Canvas;
 use sdl2::video::Window;
 #[allow(unused_imports)]
 use std::time::Duration;
-use cell::{Cell, Species};
+use cell::{Cell, Emission, Species, Spout, SpoutId};
+use api::Gravity;
 use sdl2::pixels::PixelFormatEnum;
 
 const WIDTH:  u32 = 360;
@@ -49,6 +50,14 @@ pub struct Controls {
     pub restart: bool,
     pub selected_species: Species,
     pub radius: i32,
+    pub gravity: Option<Gravity>,
+    pub zero_gravity: bool,
+    // what newly placed spouts are set up to do
+    pub spout: Spout,
+    // the spout setup being painted with in the current stroke, if any
+    pub spout_id: Option<SpoutId>,
+    // how new clones put out their copies
+    pub clone_emission: Emission,
 }
 
 impl Controls {
@@ -64,13 +73,29 @@ impl Controls {
             restart: false,
             selected_species: Species::Sand,
             radius: 4,
+            gravity: None,
+            zero_gravity: false,
+            spout: Spout { species: Species::Water, rate: 0.2, dx: 0, dy: 1 },
+            spout_id: None,
+            clone_emission: Emission::DEFAULT,
         }
     }
 
     pub fn set_species(&mut self, s: Species) {
+        // spouts put out whatever was picked last
+        if !matches!(s, Species::Spout(_) | Species::Clone(_)) {
+            self.spout.species = s;
+        }
         self.selected_species = s;
     }
 
+    // points new spouts a quarter turn clockwise
+    pub fn turn_spout(&mut self) {
+        let (dx, dy) = (self.spout.dx, self.spout.dy);
+        self.spout.dx = -dy;
+        self.spout.dy = dx;
+    }
+
     pub fn set_radius(&mut self, r: i32) {
         self.radius = r;
     }
@@ -78,6 +103,7 @@ impl Controls {
 
 fn poll_controls(controls: &mut Controls, event_pump: &mut sdl2::EventPump) -> Option<ExitCode> {
     controls.pause = false;
+    controls.zero_gravity = false;
     let mouse_state = event_pump.mouse_state();
     
     controls.mouse_pressed_l = mouse_state
@@ -96,11 +122,29 @@ fn poll_controls(controls: &mut Controls, event_pump: &mut sdl2::EventPump) -> O
             Event::Quit {..} |
             Event::KeyDown { keycode: Some(Keycode::Escape), .. } 
                 => return Some(ExitCode::Success),
-            Event::KeyDown { keycode, .. } => {
+            Event::KeyDown { keycode, keymod, .. } => {
                 use Keycode::*;
                 if let Some(k) = keycode {
+                    // shift picks from a second set of species
+                    let shifted = keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
                     controls.set_species(
-                        match k {
+                        if shifted { match k {
+                            O => Species::Oil,
+                            G => Species::Gasoline,
+                            M => Species::Methane,
+                            W => Species::Wood,
+                            S => Species::Seed,
+                            F => Species::GrassSeed,
+                            C => Species::Concrete(0, 0),
+                            A => Species::Glass,
+                            I => Species::Iron,
+                            K => Species::Copper,
+                            U => Species::Uranium,
+                            Y => Species::Plutonium,
+                            D => Species::Drain,
+                            V => Species::Void,
+                            _ => controls.selected_species
+                        }} else { match k {
                             S => Species::Sand,
                             N => Species::Stone,
                             W => Species::Water, 
@@ -112,8 +156,21 @@ fn poll_controls(controls: &mut Controls, event_pump: &mut sdl2::EventPump) -> O
                             I => Species::Ice,
                             B => Species::BlueFire,
                             O => Species::Clone(None),
+                            H => Species::Charcoal,
+                            G => Species::Gunpowder,
+                            X => Species::Nitro,
+                            Y => Species::Hydrogen,
+                            D => Species::CarbonDioxide,
+                            K => Species::Smoke,
+                            R => Species::Gravel,
+                            E => Species::Clay,
+                            M => Species::BlackHole,
+                            U => Species::Repulsor,
+                            J => Species::Wire,
+                            V => Species::Battery,
+                            Q => Species::Spout(None),
                             _ => controls.selected_species
-                        }
+                        }}
                     );
                     controls.set_radius(
                         match k {
@@ -129,6 +186,23 @@ fn poll_controls(controls: &mut Controls, event_pump: &mut sdl2::EventPump) -> O
                     match k {
                         P | Space => controls.pause = true,
                         F1 => controls.restart = true,
+                        // with shift, arrows and brackets set up new clones
+                        Up if shifted => controls.clone_emission.toggle(Emission::UP),
+                        Down if shifted => controls.clone_emission.toggle(Emission::DOWN),
+                        Left if shifted => controls.clone_emission.toggle(Emission::LEFT),
+                        Right if shifted => controls.clone_emission.toggle(Emission::RIGHT),
+                        LeftBracket if shifted => 
+                            controls.clone_emission.rate = (controls.clone_emission.rate / 2.0).max(0.01),
+                        RightBracket if shifted => 
+                            controls.clone_emission.rate = (controls.clone_emission.rate * 2.0).min(1.0),
+                        Up => controls.gravity = Some(Gravity { x: 0.0, y: -1.0 }),
+                        Down => controls.gravity = Some(Gravity::DOWN),
+                        Left => controls.gravity = Some(Gravity { x: -1.0, y: 0.0 }),
+                        Right => controls.gravity = Some(Gravity { x: 1.0, y: 0.0 }),
+                        Z => controls.zero_gravity = true,
+                        Q if shifted => controls.turn_spout(),
+                        LeftBracket => controls.spout.rate = (controls.spout.rate / 2.0).max(0.01),
+                        RightBracket => controls.spout.rate = (controls.spout.rate * 2.0).min(1.0),
                         _ => {}
                     }
                 }
@@ -150,19 +224,32 @@ fn handle_controls(controls: &mut Controls, api: &mut api::SandApi) -> Result<()
             controls.mouse_last_y) 
         {
             use Species::*;
+            // a stroke of spouts shares one setup, stored once something's placed
+            let stored = controls.spout_id.filter(|&id| api.get_spout(id).is_some());
+            let new_spout = matches!(controls.selected_species, Spout(_)) && stored.is_none();
             let cell = match controls.selected_species {
                 Clone(_) => Cell::clone(),
+                Spout(_) => Cell::new(Spout(stored.or_else(|| api.store_spout(controls.spout)))),
                 Mud(_) => Cell::mud(),
                 Flower(_) => Cell::clone(),
                 _ => Cell::new(controls.selected_species),
             };
-            api.brush(
+            let placed = api.brush(
                 point.x, 
                 point.y, 
                 controls.radius, 
                 cell
             )?;
+            if let Spout(Some(id)) = cell.species {
+                if placed || !new_spout {
+                    controls.spout_id = Some(id);
+                } else {
+                    api.remove_spout(id);
+                }
+            }
         }
+    } else {
+        controls.spout_id = None;
     }
 
     if controls.mouse_pressed_r {
@@ -179,8 +266,17 @@ fn handle_controls(controls: &mut Controls, api: &mut api::SandApi) -> Result<()
     if controls.pause {
         api.toggle_pause();
     }
+    api.set_clone_emission(controls.clone_emission);
+    if let Some(gravity) = controls.gravity.take() {
+        api.set_gravity(gravity);
+    }
+    if controls.zero_gravity {
+        api.set_gravity(if api.is_weightless() { Gravity::DOWN } else { Gravity::ZERO });
+    }
     if controls.restart {
         api.init();
+        // the old spouts went with the old map
+        controls.spout_id = None;
         controls.restart = false;
     }
 
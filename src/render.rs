@@ -12,6 +12,8 @@ struct CellRenderInfo {
     species: Species,
     heat: i16,
     grain: u8,
+    durability: u8,
+    charge: u8,
 }
 
 impl CellRenderInfo {
@@ -21,6 +23,8 @@ impl CellRenderInfo {
             species: cell.species,
             grain: cell.grain,
             heat: cell.heat,
+            durability: cell.durability,
+            charge: cell.charge,
         }
     }
 }
@@ -45,10 +49,53 @@ fn species_color(species: Species) -> Color {
         Species::BlueFire   => Color::RGB(250, 250, 255),
         Species::Empty => Color::RGB(0, 2, 5),
         Species::Salt => Color::RGB(254, 240, 200),
-        Species::SaltWater => Color::RGB(130, 130, 220),
+        Species::MuddyWater(sediment) => 
+            Color::RGB(40 + sediment * 30, 90 + sediment * 5, 200 - sediment * 40),
+        Species::SaltWater(salinity) => 
+            Color::RGB(50 + salinity * 10, 110 + salinity * 3, 225),
         Species::Border => Color::RGB(1, 1, 1),
         Species::Ice => Color::RGB(200, 240, 250),
         Species::Clone(_) => Color::RGB(170, 120, 170),
+        Species::Smoke => Color::RGB(55, 55, 60),
+        Species::Ash => Color::RGB(120, 118, 112),
+        Species::Charcoal => Color::RGB(30, 24, 22),
+        Species::Gunpowder => Color::RGB(55, 55, 65),
+        Species::Nitro => Color::RGB(190, 220, 60),
+        Species::Hydrogen => Color::RGB(190, 200, 230),
+        Species::CarbonDioxide => Color::RGB(70, 75, 70),
+        Species::Gravel => Color::RGB(105, 100, 92),
+        Species::Clay => Color::RGB(150, 90, 65),
+        Species::BlackHole => Color::RGB(40, 0, 60),
+        Species::Repulsor => Color::RGB(230, 200, 255),
+        Species::Wire => Color::RGB(190, 110, 60),
+        Species::Battery => Color::RGB(70, 90, 60),
+        Species::Oil => Color::RGB(40, 30, 20),
+        Species::Gasoline => Color::RGB(230, 210, 120),
+        Species::Methane => Color::RGB(120, 140, 110),
+        Species::Wood | Species::Trunk(_) | Species::Branch(_) => Color::RGB(110, 70, 35),
+        Species::Seed => Color::RGB(150, 120, 60),
+        Species::Leaf => Color::RGB(30, 130, 25),
+        Species::GrassSeed => Color::RGB(170, 160, 80),
+        Species::Withered => Color::RGB(120, 95, 45),
+        Species::Concrete(cure, water) => 
+            Color::RGB(110 + cure / 2, 110 + cure / 2, 105 + cure / 2 + water * 10),
+        Species::MoltenGlass => Color::RGB(255, 150, 50),
+        Species::Glass => Color::RGB(185, 215, 225),
+        Species::Basalt => Color::RGB(58, 58, 62),
+        Species::Obsidian => Color::RGB(22, 16, 32),
+        Species::Iron => Color::RGB(120, 120, 128),
+        Species::Copper => Color::RGB(185, 105, 60),
+        Species::MoltenIron => Color::RGB(255, 120, 30),
+        Species::MoltenCopper => Color::RGB(255, 90, 40),
+        Species::Rust => Color::RGB(140, 60, 25),
+        Species::Uranium => Color::RGB(90, 160, 60),
+        Species::Plutonium => Color::RGB(130, 230, 90),
+        Species::Lead => Color::RGB(80, 82, 95),
+        Species::Spout(_) => Color::RGB(60, 150, 190),
+        Species::Drain => Color::RGB(35, 35, 45),
+        Species::Void => Color::RGB(12, 0, 18),
+        Species::MoltenSalt => Color::RGB(255, 210, 150),
+        Species::Brick => Color::RGB(150, 65, 45),
         #[allow(unreachable_patterns)]
         _                       => Color::MAGENTA,
     }
@@ -71,14 +118,23 @@ fn cell_color(cell: CellRenderInfo )
     color.r = apply_grain(color.r, cell.grain, 40);
     color.g = apply_grain(color.g, cell.grain, 40);
     color.b = apply_grain(color.b, cell.grain, 40);
+    // worn cells darken
+    let wear = 0.4 + 0.6 * cell.durability as f32 / Cell::MAX_DURABILITY as f32;
+    color.r = (color.r as f32 * wear) as u8;
+    color.g = (color.g as f32 * wear) as u8;
+    color.b = (color.b as f32 * wear) as u8;
     if cell.species == Species::Fire {
         color.g = usize::min((color.g as usize + cell.grain as usize) % 255, 240) as u8;
     } else if cell.species == Species::BlueFire {
         color.g = usize::min((color.g as usize + cell.grain as usize) % 255, 200) as u8;
         color.r = usize::min((color.g as usize + cell.grain as usize) % 255, 240) as u8;
-    } else if cell.species != Species::Steam && cell.species != Species::Border {
+    } else if cell.species.gas().is_none() && cell.species != Species::Border {
         color.r = i16::min(color.r as i16 + (cell.heat - 20) / 5, 255) as u8;
     }
+    // sparks flash
+    if cell.charge == Cell::SPARK {
+        color = Color::RGB(255, 255, 160);
+    }
     color
 }
 